@@ -0,0 +1,47 @@
+//! Perft throughput on the reference positions. Run with `cargo bench`.
+
+use std::time::Instant;
+
+use chess::{perft, Board, STARTING_FEN};
+
+const POSITIONS: [(&str, &str, u32); 4] = [
+  ("initial", STARTING_FEN, 5),
+  (
+    "kiwipete",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    4,
+  ),
+  ("position 3", "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 5),
+  (
+    "position 5",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    4,
+  ),
+];
+
+fn main() {
+  let mut total_nodes = 0;
+  let mut total_secs = 0.0;
+
+  for (name, fen, depth) in POSITIONS {
+    let board = Board::from_fen(fen).unwrap();
+
+    let start = Instant::now();
+    let nodes = perft(&board, depth);
+    let secs = start.elapsed().as_secs_f64();
+
+    total_nodes += nodes;
+    total_secs += secs;
+
+    println!(
+      "{name:<12} depth {depth}  {nodes:>9} nodes  {secs:>7.3} s  {:>6.2} Mnps",
+      nodes as f64 / secs / 1e6
+    );
+  }
+
+  println!(
+    "{:<20}  {total_nodes:>9} nodes  {total_secs:>7.3} s  {:>6.2} Mnps",
+    "total",
+    total_nodes as f64 / total_secs / 1e6
+  );
+}
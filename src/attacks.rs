@@ -0,0 +1,369 @@
+//! Precomputed attack tables.
+//!
+//! Knight, king and pawn attacks are plain per-square lookups. Sliding pieces
+//! use magic bitboards: the blockers on a piece's rays are masked out of the
+//! occupancy, multiplied by a per-square magic number, and the top bits of
+//! the product index a table holding the attack set for that blocker
+//! configuration.
+
+use std::sync::LazyLock;
+
+use crate::bitboard::Bitboard;
+use crate::piece::{PieceColor, PieceType};
+
+const KNIGHT_OFFSETS: [(i32, i32); 8] = [
+  (-2, 1),
+  (-2, -1),
+  (2, 1),
+  (2, -1),
+  (1, -2),
+  (-1, -2),
+  (1, 2),
+  (-1, 2),
+];
+const KING_OFFSETS: [(i32, i32); 8] = [
+  (-1, -1),
+  (0, -1),
+  (1, -1),
+  (-1, 0),
+  (1, 0),
+  (-1, 1),
+  (0, 1),
+  (1, 1),
+];
+
+const ROOK_DIRECTIONS: [(i32, i32); 4] = [(-1, 0), (1, 0), (0, 1), (0, -1)];
+const BISHOP_DIRECTIONS: [(i32, i32); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+
+const fn inbounds(x: i32, y: i32) -> bool {
+  x >= 0 && x < 8 && y >= 0 && y < 8
+}
+
+const fn offset_table(offsets: &[(i32, i32)]) -> [Bitboard; 64] {
+  let mut table = [Bitboard::EMPTY; 64];
+  let mut sq = 0;
+  while sq < 64 {
+    let (x, y) = ((sq % 8) as i32, (sq / 8) as i32);
+    let mut bb = 0;
+    let mut i = 0;
+    while i < offsets.len() {
+      let (xt, yt) = (x + offsets[i].0, y + offsets[i].1);
+      if inbounds(xt, yt) {
+        bb |= 1 << (8 * yt + xt);
+      }
+      i += 1;
+    }
+    table[sq] = Bitboard(bb);
+    sq += 1;
+  }
+  table
+}
+
+const fn pawn_table(direction: i32) -> [Bitboard; 64] {
+  offset_table(&[(-1, direction), (1, direction)])
+}
+
+static KNIGHT_ATTACKS: [Bitboard; 64] = offset_table(&KNIGHT_OFFSETS);
+static KING_ATTACKS: [Bitboard; 64] = offset_table(&KING_OFFSETS);
+
+// indexed by the color of the attacking pawn; white pawns move towards y = 0
+static PAWN_ATTACKS: [[Bitboard; 64]; 2] = [pawn_table(-1), pawn_table(1)];
+
+// for every pair of squares on a common rank, file or diagonal: the squares
+// strictly between them, and the whole line through both (edge to edge)
+struct Rays {
+  between: [[Bitboard; 64]; 64],
+  line: [[Bitboard; 64]; 64],
+}
+
+const fn rays() -> Rays {
+  let mut rays = Rays {
+    between: [[Bitboard::EMPTY; 64]; 64],
+    line: [[Bitboard::EMPTY; 64]; 64],
+  };
+
+  let directions = [
+    ROOK_DIRECTIONS[0],
+    ROOK_DIRECTIONS[1],
+    ROOK_DIRECTIONS[2],
+    ROOK_DIRECTIONS[3],
+    BISHOP_DIRECTIONS[0],
+    BISHOP_DIRECTIONS[1],
+    BISHOP_DIRECTIONS[2],
+    BISHOP_DIRECTIONS[3],
+  ];
+
+  let mut a = 0;
+  while a < 64 {
+    let (x, y) = ((a % 8) as i32, (a / 8) as i32);
+
+    let mut d = 0;
+    while d < directions.len() {
+      let (dx, dy) = directions[d];
+
+      // full line through a in this direction and the opposite one
+      let mut line = 1u64 << a;
+      let mut sign = -1;
+      while sign <= 1 {
+        let (mut xt, mut yt) = (x + sign * dx, y + sign * dy);
+        while inbounds(xt, yt) {
+          line |= 1 << (8 * yt + xt);
+          xt += sign * dx;
+          yt += sign * dy;
+        }
+        sign += 2;
+      }
+
+      let mut between = 0u64;
+      let (mut xt, mut yt) = (x + dx, y + dy);
+      while inbounds(xt, yt) {
+        let b = (8 * yt + xt) as usize;
+        rays.between[a][b] = Bitboard(between);
+        rays.line[a][b] = Bitboard(line);
+        between |= 1 << b;
+        xt += dx;
+        yt += dy;
+      }
+
+      d += 1;
+    }
+
+    a += 1;
+  }
+
+  rays
+}
+
+static RAYS: Rays = rays();
+
+// Found by random search over sparse 64-bit numbers for this square
+// numbering; any number that maps every blocker subset without a
+// destructive collision works.
+#[rustfmt::skip]
+const ROOK_MAGICS: [u64; 64] = [
+  0x2080002080400010, 0x00c0002001401000, 0x2100110008402002, 0x0880080081041000,
+  0x0200020020041008, 0x2300040008010012, 0x0c00283004008201, 0x0180010000407a80,
+  0x0168800080400020, 0x0010400040201000, 0x1001002001001048, 0x1001002408100100,
+  0x0801000408010012, 0x4001000209000400, 0x08a20004c8020001, 0x2002801145002280,
+  0x0080860021004200, 0x001000c009402002, 0x00b0002004002800, 0x100a808010020800,
+  0x8101010008000410, 0x0244008002000480, 0x0000040010810208, 0x2000020000448534,
+  0x4104400480008033, 0x0000810100204000, 0x0440430900200010, 0x4600240900100100,
+  0x0060080080040080, 0x0001000300080400, 0x0004084400011002, 0x0023040200008041,
+  0x0580050043002080, 0x0400804002802008, 0x0001002001004010, 0x1000200901001000,
+  0x4410800801800c00, 0xa012003806001004, 0x0020100104008802, 0x0004808402000041,
+  0x0010400170898000, 0x0080500020004004, 0x1040408012020020, 0x8010040008004040,
+  0x2001080100110004, 0x0000020004008080, 0x0021010810040002, 0x0800008c43020024,
+  0x0000800021005100, 0x0070201040008080, 0x0000d04282006a00, 0x0010014400080240,
+  0x0001080110050100, 0x0012000810240600, 0x0402000801040200, 0x028100108a004100,
+  0x0050800300102045, 0x8208210040120882, 0x8010600101183441, 0x020b000910006045,
+  0x0241001002480005, 0x0081000400880241, 0x0000009008024124, 0x0048122980410402,
+];
+
+#[rustfmt::skip]
+const BISHOP_MAGICS: [u64; 64] = [
+  0x0848020822040013, 0x8010a40085821200, 0x0008008430840822, 0x0808048108040000,
+  0x1304042100008104, 0x5001012010204023, 0x81048801b8200420, 0x200a008084012000,
+  0x0040102001042084, 0x840a505042428020, 0x0000700102202920, 0x44101c0c10800002,
+  0x0040040422000000, 0x0180020802090202, 0x4020020811041202, 0x000104308c042000,
+  0x4140661002424400, 0x0028012008010460, 0x0188062102002a00, 0x0014004840102008,
+  0x0105000290400002, 0x8001022200410400, 0x104a041918013446, 0x008a000082008238,
+  0x04a0060008100430, 0x0008220008820801, 0x2508041208005010, 0x4008080200202020,
+  0x2441001013004000, 0x0030008060407000, 0x4008108000420800, 0x0012021050290100,
+  0x0210080482200500, 0xcc01112048100480, 0x0020402806500440, 0x00048e0080580080,
+  0x0040102020020080, 0x0028010440080807, 0x4601041108008800, 0x8040810e04104200,
+  0x901210110400088a, 0xa003080212081050, 0x00c1004048401004, 0x900000a014400800,
+  0x0008021040405401, 0x4020008206002090, 0x0004190424030100, 0x0424008a02026250,
+  0x8004088250900040, 0x1c00430088a04200, 0x0001020094040001, 0x8040210020880061,
+  0x2010040450442032, 0x0800840850044001, 0x0004040802140004, 0x0004080a04222020,
+  0x8088802110022000, 0x1081a10416114400, 0x0205010a24060820, 0x0000000720411080,
+  0x1008000208430400, 0x580c026028810840, 0x802020441020a110, 0x12c0022401020018,
+];
+
+// walks each ray until it leaves the board or hits a blocker (inclusive);
+// only used to fill the tables
+fn slow_slider_attacks(sq: u32, occupied: u64, directions: &[(i32, i32); 4]) -> u64 {
+  let (x, y) = ((sq % 8) as i32, (sq / 8) as i32);
+  let mut bb = 0;
+
+  for (xd, yd) in directions {
+    let mut xt = x + xd;
+    let mut yt = y + yd;
+    while inbounds(xt, yt) {
+      let bit = 1 << (8 * yt + xt);
+      bb |= bit;
+      if occupied & bit != 0 {
+        break;
+      }
+
+      xt += xd;
+      yt += yd;
+    }
+  }
+
+  bb
+}
+
+// squares whose occupancy matters for a slider on `sq`: its rays without the
+// last square of each one, since a piece there can't block anything further
+fn relevant_mask(sq: u32, directions: &[(i32, i32); 4]) -> u64 {
+  let (x, y) = ((sq % 8) as i32, (sq / 8) as i32);
+  let mut bb = 0;
+
+  for (xd, yd) in directions {
+    let mut xt = x + xd;
+    let mut yt = y + yd;
+    while inbounds(xt + xd, yt + yd) {
+      bb |= 1 << (8 * yt + xt);
+      xt += xd;
+      yt += yd;
+    }
+  }
+
+  bb
+}
+
+#[derive(Copy, Clone, Default)]
+struct Magic {
+  mask: u64,
+  magic: u64,
+  shift: u32,
+  offset: usize,
+}
+
+impl Magic {
+  #[inline(always)]
+  fn index(&self, occupied: Bitboard) -> usize {
+    self.offset + ((occupied.0 & self.mask).wrapping_mul(self.magic) >> self.shift) as usize
+  }
+}
+
+struct SliderTables {
+  rook: [Magic; 64],
+  bishop: [Magic; 64],
+  // attack sets for every square and blocker configuration, both piece
+  // types, addressed through Magic::offset
+  attacks: Vec<Bitboard>,
+}
+
+fn init_magics(
+  magics: &mut [Magic; 64],
+  numbers: &[u64; 64],
+  directions: &[(i32, i32); 4],
+  attacks: &mut Vec<Bitboard>,
+) {
+  for sq in 0..64u32 {
+    let mask = relevant_mask(sq, directions);
+    let bits = mask.count_ones();
+    let magic = Magic {
+      mask,
+      magic: numbers[sq as usize],
+      shift: 64 - bits,
+      offset: attacks.len(),
+    };
+
+    attacks.resize(attacks.len() + (1 << bits), Bitboard::EMPTY);
+
+    // enumerate every subset of the mask (Carry-Rippler trick)
+    let mut subset = 0u64;
+    loop {
+      let idx = magic.index(Bitboard(subset));
+      let bb = Bitboard(slow_slider_attacks(sq, subset, directions));
+
+      // the attack set is never empty, so an empty entry means unused; a
+      // different non-empty entry would mean the magic number is broken
+      debug_assert!(attacks[idx].is_empty() || attacks[idx] == bb);
+      attacks[idx] = bb;
+
+      subset = subset.wrapping_sub(mask) & mask;
+      if subset == 0 {
+        break;
+      }
+    }
+
+    magics[sq as usize] = magic;
+  }
+}
+
+static SLIDER_TABLES: LazyLock<SliderTables> = LazyLock::new(|| {
+  let mut tables = SliderTables {
+    rook: [Magic::default(); 64],
+    bishop: [Magic::default(); 64],
+    attacks: Vec::new(),
+  };
+
+  init_magics(
+    &mut tables.rook,
+    &ROOK_MAGICS,
+    &ROOK_DIRECTIONS,
+    &mut tables.attacks,
+  );
+  init_magics(
+    &mut tables.bishop,
+    &BISHOP_MAGICS,
+    &BISHOP_DIRECTIONS,
+    &mut tables.attacks,
+  );
+
+  tables
+});
+
+#[inline(always)]
+pub fn knight_attacks(sq: u32) -> Bitboard {
+  KNIGHT_ATTACKS[sq as usize]
+}
+
+#[inline(always)]
+pub fn king_attacks(sq: u32) -> Bitboard {
+  KING_ATTACKS[sq as usize]
+}
+
+/// Squares a pawn of `color` on `sq` attacks (diagonally forward).
+#[inline(always)]
+pub fn pawn_attacks(color: PieceColor, sq: u32) -> Bitboard {
+  PAWN_ATTACKS[color as usize][sq as usize]
+}
+
+#[inline(always)]
+pub fn rook_attacks(sq: u32, occupied: Bitboard) -> Bitboard {
+  let tables = &*SLIDER_TABLES;
+  tables.attacks[tables.rook[sq as usize].index(occupied)]
+}
+
+#[inline(always)]
+pub fn bishop_attacks(sq: u32, occupied: Bitboard) -> Bitboard {
+  let tables = &*SLIDER_TABLES;
+  tables.attacks[tables.bishop[sq as usize].index(occupied)]
+}
+
+#[inline(always)]
+pub fn queen_attacks(sq: u32, occupied: Bitboard) -> Bitboard {
+  rook_attacks(sq, occupied) | bishop_attacks(sq, occupied)
+}
+
+/// Squares strictly between `a` and `b` if they share a rank, file or
+/// diagonal, otherwise empty.
+#[inline(always)]
+pub fn between(a: u32, b: u32) -> Bitboard {
+  RAYS.between[a as usize][b as usize]
+}
+
+/// The whole rank, file or diagonal through `a` and `b` (including both), or
+/// empty if they aren't on one.
+#[inline(always)]
+pub fn line(a: u32, b: u32) -> Bitboard {
+  RAYS.line[a as usize][b as usize]
+}
+
+/// Squares attacked by a non-pawn piece on `sq`. Pawns attack differently
+/// depending on their color, use `pawn_attacks` for those.
+#[inline]
+pub fn piece_attacks(class: PieceType, sq: u32, occupied: Bitboard) -> Bitboard {
+  match class {
+    PieceType::Knight => knight_attacks(sq),
+    PieceType::Bishop => bishop_attacks(sq, occupied),
+    PieceType::Rook => rook_attacks(sq, occupied),
+    PieceType::Queen => queen_attacks(sq, occupied),
+    PieceType::King => king_attacks(sq),
+    PieceType::Pawn => unreachable!("pawn attacks depend on color"),
+  }
+}
@@ -0,0 +1,120 @@
+//! Build opening books from PGN collections and look positions up in them.
+//!
+//! Usage:
+//!   book build [--max-ply PLIES] OUT.bin FILE.pgn...
+//!   book probe BOOK.bin [FEN]
+//!
+//! `build` replays every game of the PGN files and weights the moves played
+//! in the first PLIES plies (20 by default) by the games' results; see
+//! `BookBuilder`. A file that can't be read, or a game that can't (shown
+//! as `path:line:column`), is reported and left out while the rest go in;
+//! the book is still written, but the exit status is a failure. Books are
+//! written in the crate's native format, which `Book::load` reads back.
+//! `probe` lists the book moves for a position, the starting position if
+//! no FEN is given; it reads Polyglot `.bin` books as well.
+
+use std::env;
+use std::fs;
+use std::process::ExitCode;
+
+use chess::{parse_pgn_games, Board, Book, BookBuilder};
+
+const DEFAULT_MAX_PLY: usize = 20;
+
+const USAGE: &str =
+  "usage: book build [--max-ply PLIES] OUT.bin FILE.pgn...\n       book probe BOOK.bin [FEN]";
+
+fn build(args: &[String]) -> Result<(), String> {
+  let (max_ply, args) = match args {
+    [flag, plies, rest @ ..] if flag == "--max-ply" => (
+      plies
+        .parse()
+        .map_err(|_| format!("invalid ply count: '{plies}'"))?,
+      rest,
+    ),
+    rest => (DEFAULT_MAX_PLY, rest),
+  };
+  let [out, files @ ..] = args else {
+    return Err(USAGE.to_string());
+  };
+  if files.is_empty() {
+    return Err(USAGE.to_string());
+  }
+
+  let mut builder = BookBuilder::new(max_ply);
+  let mut skipped = 0;
+  for path in files {
+    let text = match fs::read_to_string(path) {
+      Ok(text) => text,
+      Err(err) => {
+        eprintln!("{path}: {err}");
+        skipped += 1;
+        continue;
+      }
+    };
+    for game in parse_pgn_games(&text) {
+      match game {
+        Ok(game) => builder.add_game(&game),
+        Err(err) => {
+          eprintln!("{path}:{}:{}: {}", err.line, err.column, err.kind);
+          skipped += 1;
+        }
+      }
+    }
+  }
+
+  let book = builder.build();
+  book.save(out).map_err(|err| format!("{out}: {err}"))?;
+  println!(
+    "{out}: {} entries from {} games",
+    book.len(),
+    builder.games()
+  );
+  if skipped > 0 {
+    return Err(format!("left out {skipped} unreadable games or files"));
+  }
+  Ok(())
+}
+
+fn probe(args: &[String]) -> Result<(), String> {
+  let (path, board) = match args {
+    [path] => (path, Board::new()),
+    [path, fen] => (path, Board::from_fen(fen).map_err(|err| err.to_string())?),
+    _ => return Err(USAGE.to_string()),
+  };
+
+  let book = Book::load(path).map_err(|err| format!("{path}: {err}"))?;
+  let moves = book.moves(&board);
+  let total: u64 = moves.iter().map(|&(_, weight)| weight as u64).sum();
+
+  if moves.is_empty() {
+    println!("no book moves");
+  }
+  for (mv, weight) in moves {
+    println!(
+      "{:<8} {:>6} {:>5.1}%",
+      mv.to_san(&board),
+      weight,
+      100.0 * weight as f64 / total as f64
+    );
+  }
+  Ok(())
+}
+
+fn main() -> ExitCode {
+  let args: Vec<String> = env::args().skip(1).collect();
+
+  let result = match args.split_first() {
+    Some((command, rest)) if command == "build" => build(rest),
+    Some((command, rest)) if command == "probe" => probe(rest),
+    _ => Err(USAGE.to_string()),
+  };
+
+  match result {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(err) => {
+      eprintln!("{err}");
+      ExitCode::FAILURE
+    }
+  }
+}
@@ -0,0 +1,137 @@
+//! Run EPD test suites (Win At Chess, STS, ...) against the search.
+//!
+//! Usage: epd [--depth PLIES | --time MILLISECONDS] FILE...
+//!
+//! Each position is searched to a fixed depth (3 plies by default) or, with
+//! `--time`, by iterative deepening until the time is up. The position is
+//! solved if the move found is one of its `bm` moves and none of its `am`
+//! moves. Every position is reported, followed by the overall score.
+
+use std::env;
+use std::fs;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use chess::search::{minimax, minimax_timed};
+use chess::{Board, Epd, Move};
+
+const DEFAULT_DEPTH: u32 = 3;
+// how deep iterative deepening may go under a time limit
+const MAX_DEPTH: u32 = 64;
+
+enum Limit {
+  Depth(u32),
+  Time(Duration),
+}
+
+// the move the search picks in `board`, and the depth it got to
+fn search(board: Board, limit: &Limit) -> (Option<Move>, u32) {
+  match *limit {
+    Limit::Depth(depth) => (minimax(board, depth, i32::MIN, i32::MAX).0, depth),
+    Limit::Time(time) => {
+      let (mv, _, depth) = minimax_timed(board, &[], MAX_DEPTH, time);
+      (mv, depth)
+    }
+  }
+}
+
+fn sans(board: &Board, moves: &[Move]) -> String {
+  let sans: Vec<String> = moves.iter().map(|mv| mv.to_san(board)).collect();
+  sans.join(" ")
+}
+
+fn parse_args(args: &[String]) -> Option<(Limit, &[String])> {
+  let (limit, files) = match args {
+    [flag, value, files @ ..] if flag == "--depth" => (Limit::Depth(value.parse().ok()?), files),
+    [flag, value, files @ ..] if flag == "--time" => (
+      Limit::Time(Duration::from_millis(value.parse().ok()?)),
+      files,
+    ),
+    files => (Limit::Depth(DEFAULT_DEPTH), files),
+  };
+
+  if files.is_empty() {
+    None
+  } else {
+    Some((limit, files))
+  }
+}
+
+fn main() -> ExitCode {
+  let args: Vec<String> = env::args().skip(1).collect();
+  let Some((limit, files)) = parse_args(&args) else {
+    eprintln!("usage: epd [--depth PLIES | --time MILLISECONDS] FILE...");
+    return ExitCode::FAILURE;
+  };
+
+  let (mut solved, mut total, mut invalid) = (0, 0, 0);
+  let start = Instant::now();
+
+  for path in files {
+    let text = match fs::read_to_string(path) {
+      Ok(text) => text,
+      Err(err) => {
+        eprintln!("{path}: {err}");
+        return ExitCode::FAILURE;
+      }
+    };
+
+    for (i, line) in text.lines().enumerate() {
+      if line.trim().is_empty() || line.starts_with('#') {
+        continue;
+      }
+
+      let epd: Epd = match line.parse() {
+        Ok(epd) => epd,
+        Err(err) => {
+          eprintln!("{path}:{}: {err}", i + 1);
+          invalid += 1;
+          continue;
+        }
+      };
+
+      let id = epd
+        .id
+        .clone()
+        .unwrap_or_else(|| format!("{path}:{}", i + 1));
+      let time = Instant::now();
+      let (mv, depth) = search(epd.board, &limit);
+
+      let ok = mv.is_some_and(|mv| epd.is_solved_by(mv));
+      total += 1;
+      if ok {
+        solved += 1;
+      }
+
+      let mut expected = String::new();
+      if !epd.best_moves.is_empty() {
+        expected += &format!(" bm {}", sans(&epd.board, &epd.best_moves));
+      }
+      if !epd.avoid_moves.is_empty() {
+        expected += &format!(" am {}", sans(&epd.board, &epd.avoid_moves));
+      }
+
+      println!(
+        "{id:<16} {:<6} {:<8}{expected} (depth {depth}, {:?})",
+        if ok { "solved" } else { "FAILED" },
+        mv.map_or("-".to_string(), |mv| mv.to_san(&epd.board)),
+        time.elapsed(),
+      );
+    }
+  }
+
+  let percent = if total == 0 {
+    0.0
+  } else {
+    100.0 * solved as f64 / total as f64
+  };
+  println!(
+    "solved {solved}/{total} ({percent:.1}%) in {:?}",
+    start.elapsed()
+  );
+  if invalid > 0 {
+    println!("{invalid} invalid position(s) skipped");
+  }
+
+  ExitCode::SUCCESS
+}
@@ -0,0 +1,69 @@
+//! Check PGN files and print their games back out in export format.
+//!
+//! Usage: pgn [--summary] FILE...
+//!
+//! Every game is replayed, so an illegal move anywhere (variations included)
+//! is reported as `FILE:LINE:COLUMN: message`. With `--summary` each game is
+//! printed as a single line instead of in full.
+
+use std::env;
+use std::fs;
+use std::process::ExitCode;
+
+use chess::pgn::result_token;
+use chess::{parse_pgn, Game};
+
+fn summary(game: &Game) -> String {
+  let tag = |name| game.tag(name).unwrap_or("?");
+  format!(
+    "{} - {} {} ({} plies)",
+    tag("White"),
+    tag("Black"),
+    result_token(game.result()),
+    game.moves().len()
+  )
+}
+
+fn main() -> ExitCode {
+  let mut args: Vec<String> = env::args().skip(1).collect();
+  let short = args.first().is_some_and(|arg| arg == "--summary");
+  if short {
+    args.remove(0);
+  }
+
+  if args.is_empty() {
+    eprintln!("usage: pgn [--summary] FILE...");
+    return ExitCode::FAILURE;
+  }
+
+  let mut status = ExitCode::SUCCESS;
+
+  for path in &args {
+    let games = match fs::read_to_string(path) {
+      Ok(text) => parse_pgn(&text),
+      Err(err) => {
+        eprintln!("{path}: {err}");
+        status = ExitCode::FAILURE;
+        continue;
+      }
+    };
+
+    match games {
+      Ok(games) => {
+        for (i, game) in games.iter().enumerate() {
+          if short {
+            println!("{path} #{}: {}", i + 1, summary(game));
+          } else {
+            print!("{}", game.to_pgn());
+          }
+        }
+      }
+      Err(err) => {
+        eprintln!("{path}:{}:{}: {}", err.line, err.column, err.kind);
+        status = ExitCode::FAILURE;
+      }
+    }
+  }
+
+  status
+}
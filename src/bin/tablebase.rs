@@ -0,0 +1,99 @@
+//! Generate endgame tables and look positions up in them.
+//!
+//! Usage:
+//!   tablebase generate DIR MATERIAL...
+//!   tablebase probe DIR FEN
+//!
+//! `generate` builds the tables for each MATERIAL, such as `KQK`, `KRK`,
+//! `KPK` or `KBNK`, along with those for the endings they turn into, and
+//! writes them all to DIR. Tables already in DIR are reused. `probe` prints
+//! the outcome of a position with best play, then every move with what it
+//! leads to, marking the ones that keep that outcome.
+
+use std::env;
+use std::io::ErrorKind;
+use std::process::ExitCode;
+use std::time::Instant;
+
+use chess::{Board, Outcome, Tablebase, TablebaseError};
+
+const USAGE: &str = "usage: tablebase generate DIR MATERIAL...\n       tablebase probe DIR FEN";
+
+fn describe(outcome: Outcome) -> String {
+  match outcome {
+    Outcome::Win(plies) => format!("win, mate in {plies} plies"),
+    Outcome::Draw => "draw".to_string(),
+    Outcome::Loss(0) => "checkmated".to_string(),
+    Outcome::Loss(plies) => format!("loss, mated in {plies} plies"),
+  }
+}
+
+fn generate(args: &[String]) -> Result<(), String> {
+  let [dir, materials @ ..] = args else {
+    return Err(USAGE.to_string());
+  };
+  if materials.is_empty() {
+    return Err(USAGE.to_string());
+  }
+
+  let mut tablebase = match Tablebase::load_dir(dir) {
+    Ok(tablebase) => tablebase,
+    Err(TablebaseError::Io(err)) if err.kind() == ErrorKind::NotFound => Tablebase::new(),
+    Err(err) => return Err(format!("{dir}: {err}")),
+  };
+
+  for material in materials {
+    let start = Instant::now();
+    tablebase
+      .generate(material)
+      .map_err(|err| err.to_string())?;
+    println!("{material}: done in {:?}", start.elapsed());
+  }
+
+  tablebase
+    .save_dir(dir)
+    .map_err(|err| format!("{dir}: {err}"))?;
+  println!("{dir}: {}", tablebase.tables().join(" "));
+  Ok(())
+}
+
+fn probe(args: &[String]) -> Result<(), String> {
+  let [dir, fen] = args else {
+    return Err(USAGE.to_string());
+  };
+  let board = Board::from_fen(fen).map_err(|err| err.to_string())?;
+  let tablebase = Tablebase::load_dir(dir).map_err(|err| format!("{dir}: {err}"))?;
+
+  let Some(outcome) = tablebase.probe(&board) else {
+    println!("not in the tables");
+    return Ok(());
+  };
+  println!("{}", describe(outcome));
+
+  for mv in board.moves_for_player() {
+    let after = board.apply_move(mv);
+    if let Some(result) = tablebase.probe(&after).map(Outcome::before) {
+      let marker = if result == outcome { "*" } else { " " };
+      println!("{marker} {:<8} {}", mv.to_san(&board), describe(result));
+    }
+  }
+  Ok(())
+}
+
+fn main() -> ExitCode {
+  let args: Vec<String> = env::args().skip(1).collect();
+
+  let result = match args.split_first() {
+    Some((command, rest)) if command == "generate" => generate(rest),
+    Some((command, rest)) if command == "probe" => probe(rest),
+    _ => Err(USAGE.to_string()),
+  };
+
+  match result {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(err) => {
+      eprintln!("{err}");
+      ExitCode::FAILURE
+    }
+  }
+}
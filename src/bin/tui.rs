@@ -0,0 +1,387 @@
+//! Play in a terminal, for when there's no display to open the SFML window
+//! on (over SSH, say).
+//!
+//! Usage: tui [--no-color] [FEN]
+//!
+//! The board is drawn with Unicode pieces and ANSI colors; `--no-color` or
+//! a set `NO_COLOR` variable switches to plain letters. Moves are typed in
+//! SAN (`Nf3`, `exd5`, `O-O`, `e8=Q`) or as coordinates (`g1f3`, `e7e8q`).
+//! Type `help` for the other commands.
+//!
+//! The engine plays like the GUI's: from `book.bin` (native or Polyglot)
+//! while the position is in the book, perfectly from the tables in `tablebases/` once they cover
+//! it, and by searching otherwise.
+
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+use std::time::Instant;
+
+use chess::frontend::{
+  load_book, load_tablebase, next_random, print_outcome, random_seed, BOOK_PATH, TABLEBASE_DIR,
+};
+use chess::search::minimax_with_tablebase;
+use chess::{is_in_check, Board, Book, Game, GameStatus, Move, PieceColor, PieceType, Tablebase};
+
+const DEFAULT_DEPTH: u32 = 3;
+
+// the GUI's square colors, and the one for the last move's squares
+const LIGHT: (u8, u8, u8) = (137, 224, 143);
+const DARK: (u8, u8, u8) = (15, 122, 56);
+const HIGHLIGHT: (u8, u8, u8) = (205, 210, 106);
+
+const HELP: &str = "\
+  <move>          play a move, in SAN (Nf3, O-O, e8=Q) or coordinates (g1f3, e7e8q)
+  go              let the engine move
+  play <side>     have the engine answer for white, black, both or none
+  depth <plies>   set the engine's search depth
+  undo            take back the last move
+  new             start a new game
+  fen [<fen>]     load a position, or print the current one
+  moves           list the legal moves
+  pgn             print the game so far
+  flip            turn the board around
+  help            show this text
+  quit            leave";
+
+struct Tui {
+  game: Game,
+  depth: u32,
+  // indexed by PieceColor: whether the engine moves for that side
+  engine_sides: [bool; 2],
+  flipped: bool,
+  color: bool,
+  book: Book,
+  tablebase: Tablebase,
+  // xorshift64 state for picking book moves
+  random: u64,
+}
+
+fn side_name(color: PieceColor) -> &'static str {
+  if color.is_white() {
+    "White"
+  } else {
+    "Black"
+  }
+}
+
+fn glyph(class: PieceType) -> char {
+  match class {
+    PieceType::Pawn => '♟',
+    PieceType::Knight => '♞',
+    PieceType::Bishop => '♝',
+    PieceType::Rook => '♜',
+    PieceType::Queen => '♛',
+    PieceType::King => '♚',
+  }
+}
+
+// The board as text. With colors each square is a background-colored cell
+// holding a filled piece glyph in white or black; without them it's the
+// pieces' FEN letters and dots.
+fn draw_board(board: &Board, last_move: Option<Move>, flipped: bool, color: bool) -> String {
+  let mut out = String::new();
+  let order = |i: u32| if flipped { 7 - i } else { i };
+
+  for row in 0..8 {
+    let y = order(row);
+    out += &format!(" {} ", 8 - y);
+
+    for col in 0..8 {
+      let x = order(col);
+      let sq = 8 * y + x;
+      let piece = board[sq];
+
+      if !color {
+        out.push(piece.map_or('.', |p| p.to_char()));
+        out.push(' ');
+        continue;
+      }
+
+      let (r, g, b) = if last_move.is_some_and(|mv| mv.from_sq() == sq || mv.to_sq() == sq) {
+        HIGHLIGHT
+      } else if (x + y) % 2 == 0 {
+        LIGHT
+      } else {
+        DARK
+      };
+      out += &format!("\x1b[48;2;{r};{g};{b}m");
+      match piece {
+        Some(p) if p.color.is_white() => {
+          out += &format!("\x1b[1;38;2;255;255;255m {} ", glyph(p.class))
+        }
+        Some(p) => out += &format!("\x1b[1;38;2;0;0;0m {} ", glyph(p.class)),
+        None => out += "   ",
+      }
+      out += "\x1b[0m";
+    }
+    out.push('\n');
+  }
+
+  let files: String = (0..8)
+    .map(|col| {
+      let file = (b'a' + order(col) as u8) as char;
+      if color {
+        format!(" {file} ")
+      } else {
+        format!("{file} ")
+      }
+    })
+    .collect();
+  out += &format!("   {}\n", files.trim_end());
+  out
+}
+
+// `text` as a legal move in `board`, read as SAN first and then as
+// coordinates
+fn parse_move(board: &Board, text: &str) -> Result<Move, String> {
+  let san_error = match board.parse_san(text) {
+    Ok(mv) => return Ok(mv),
+    Err(err) => err,
+  };
+
+  let Ok(mv) = text.parse::<Move>() else {
+    return Err(san_error.to_string());
+  };
+  // the parsed move doesn't know if it's a capture, castling or en passant,
+  // so play the generated move with the same squares
+  board.try_apply(mv).map_err(|err| err.to_string())?;
+  Ok(
+    board
+      .moves_for_player()
+      .iter()
+      .copied()
+      .find(|m| {
+        m.from_sq() == mv.from_sq() && m.to_sq() == mv.to_sq() && m.promotion() == mv.promotion()
+      })
+      .expect("try_apply accepted the move"),
+  )
+}
+
+impl Tui {
+  fn show(&self) {
+    let board = self.game.board();
+    println!();
+    print!(
+      "{}",
+      draw_board(
+        board,
+        self.game.moves().last().copied(),
+        self.flipped,
+        self.color
+      )
+    );
+
+    let status = self.game.status();
+    match status {
+      GameStatus::Ongoing => {}
+      _ if status.is_claimable() => println!("{} can be claimed", status),
+      _ => println!("Game over: {}", status),
+    }
+    if !self.is_over() && is_in_check(board, board.side_to_move()) {
+      println!("{} is in check", side_name(board.side_to_move()));
+    }
+  }
+
+  fn play(&mut self, mv: Move) {
+    println!("{}", mv.to_san(self.game.board()));
+    self.game.push(mv);
+    self.show();
+  }
+
+  fn engine_move(&mut self) {
+    let board = *self.game.board();
+    if board.moves_for_player().is_empty() {
+      println!("No legal moves");
+      return;
+    }
+
+    if let Some(mv) = self.book.pick(&board, next_random(&mut self.random)) {
+      println!("[Engine] book move");
+      self.play(mv);
+      return;
+    }
+
+    if let Some(outcome) = self.tablebase.probe(&board) {
+      print_outcome(&board, outcome);
+    }
+
+    let start = Instant::now();
+    let positions = self.game.positions();
+    let (mv, value) = minimax_with_tablebase(
+      board,
+      &positions[..positions.len() - 1],
+      self.depth,
+      &self.tablebase,
+    );
+    println!(
+      "[Engine] depth {} ply, eval {}, {:?}",
+      self.depth,
+      value,
+      start.elapsed()
+    );
+
+    self.play(mv.expect("the search returns a move when there are legal moves"));
+  }
+
+  // let the engine move for as long as it's its turn
+  fn engine_replies(&mut self) {
+    while self.engine_sides[self.game.board().side_to_move() as usize]
+      && !self.game.board().moves_for_player().is_empty()
+      && !self.is_over()
+    {
+      self.engine_move();
+    }
+  }
+
+  // over for good: mate, or a draw nobody has to claim
+  fn is_over(&self) -> bool {
+    let status = self.game.status();
+    status.is_over() && !status.is_claimable()
+  }
+
+  // false once the user wants to quit
+  fn command(&mut self, line: &str) -> bool {
+    let (command, arg) = match line.split_once(char::is_whitespace) {
+      Some((command, arg)) => (command, arg.trim()),
+      None => (line, ""),
+    };
+
+    match (command, arg) {
+      ("quit" | "exit", "") => return false,
+      ("help", "") => println!("{HELP}"),
+      ("go", "") => {
+        if self.is_over() {
+          println!("The game is over");
+        } else {
+          self.engine_move();
+        }
+      }
+      ("play", side) => {
+        self.engine_sides = match side {
+          "white" => [true, false],
+          "black" => [false, true],
+          "both" => [true, true],
+          "none" => [false, false],
+          _ => {
+            println!("usage: play white|black|both|none");
+            return true;
+          }
+        };
+        self.engine_replies();
+      }
+      ("depth", plies) => match plies.parse() {
+        Ok(depth) if depth > 0 => {
+          self.depth = depth;
+          println!("Depth set to {depth} ply");
+        }
+        _ => println!("usage: depth <plies>, at least 1"),
+      },
+      ("undo" | "takeback", "") => match self.game.pop() {
+        Some(_) => self.show(),
+        None => println!("No moves to take back"),
+      },
+      ("new", "") => {
+        self.game = Game::new();
+        self.show();
+        self.engine_replies();
+      }
+      ("fen", "") => println!("{}", self.game.board().to_fen()),
+      ("fen", fen) => match Board::from_fen(fen) {
+        Ok(board) => {
+          self.game = Game::from_position(board);
+          self.show();
+          self.engine_replies();
+        }
+        Err(err) => println!("Invalid FEN: {err}"),
+      },
+      ("moves", "") => {
+        let board = self.game.board();
+        let moves: Vec<String> = board
+          .moves_for_player()
+          .iter()
+          .map(|mv| mv.to_san(board))
+          .collect();
+        println!("{}", moves.join(" "));
+      }
+      ("pgn", "") => print!("{}", self.game.to_pgn()),
+      ("flip", "") => {
+        self.flipped = !self.flipped;
+        self.show();
+      }
+      (text, "") => {
+        if self.is_over() {
+          println!("The game is over; undo, new or fen to go on");
+          return true;
+        }
+        match parse_move(self.game.board(), text) {
+          Ok(mv) => {
+            self.play(mv);
+            self.engine_replies();
+          }
+          Err(err) => println!("{err} (type help for the commands)"),
+        }
+      }
+      _ => println!("Unknown command: {line} (type help for the commands)"),
+    }
+    true
+  }
+}
+
+fn main() -> ExitCode {
+  let mut args: Vec<String> = env::args().skip(1).collect();
+  let mut color = env::var_os("NO_COLOR").is_none_or(|v| v.is_empty());
+  if let Some(i) = args.iter().position(|a| a == "--no-color") {
+    args.remove(i);
+    color = false;
+  }
+
+  let game = if args.is_empty() {
+    Game::new()
+  } else {
+    match Board::from_fen(&args.join(" ")) {
+      Ok(board) => Game::from_position(board),
+      Err(err) => {
+        eprintln!("invalid FEN: {err}");
+        eprintln!("usage: tui [--no-color] [FEN]");
+        return ExitCode::FAILURE;
+      }
+    }
+  };
+
+  let mut tui = Tui {
+    game,
+    depth: DEFAULT_DEPTH,
+    engine_sides: [false; 2],
+    flipped: false,
+    color,
+    // the same files the GUI plays from
+    book: load_book(BOOK_PATH),
+    tablebase: load_tablebase(TABLEBASE_DIR),
+    random: random_seed(),
+  };
+  tui.show();
+
+  let stdin = io::stdin();
+  let mut lines = stdin.lock().lines();
+  loop {
+    let board = tui.game.board();
+    print!(
+      "{} ({})> ",
+      side_name(board.side_to_move()),
+      board.fullmove_number()
+    );
+    io::stdout().flush().ok();
+
+    let Some(Ok(line)) = lines.next() else {
+      println!();
+      break;
+    };
+    let line = line.trim();
+    if !line.is_empty() && !tui.command(line) {
+      break;
+    }
+  }
+
+  ExitCode::SUCCESS
+}
@@ -0,0 +1,142 @@
+use std::fmt;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+/// A set of squares, one bit per square.
+///
+/// Bits use the same numbering as the board array: bit `8 * y + x`, so bit 0
+/// is a8 and bit 63 is h1.
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+  pub const EMPTY: Bitboard = Bitboard(0);
+
+  #[inline(always)]
+  pub const fn from_square(sq: u32) -> Self {
+    Bitboard(1 << sq)
+  }
+
+  #[inline(always)]
+  pub const fn from_coord((x, y): (u32, u32)) -> Self {
+    Bitboard(1 << (8 * y + x))
+  }
+
+  #[inline(always)]
+  pub const fn contains(self, sq: u32) -> bool {
+    self.0 & (1 << sq) != 0
+  }
+
+  #[inline(always)]
+  pub const fn is_empty(self) -> bool {
+    self.0 == 0
+  }
+
+  #[inline(always)]
+  pub const fn count(self) -> u32 {
+    self.0.count_ones()
+  }
+
+  /// Lowest set square, if any.
+  #[inline(always)]
+  pub const fn first(self) -> Option<u32> {
+    if self.0 == 0 {
+      None
+    } else {
+      Some(self.0.trailing_zeros())
+    }
+  }
+
+  #[inline(always)]
+  pub fn squares(self) -> Squares {
+    Squares(self.0)
+  }
+}
+
+/// Iterator over the squares of a bitboard, lowest first.
+pub struct Squares(u64);
+
+impl Iterator for Squares {
+  type Item = u32;
+
+  #[inline(always)]
+  fn next(&mut self) -> Option<u32> {
+    if self.0 == 0 {
+      None
+    } else {
+      let sq = self.0.trailing_zeros();
+      // clear lowest set bit
+      self.0 &= self.0 - 1;
+      Some(sq)
+    }
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let n = self.0.count_ones() as usize;
+    (n, Some(n))
+  }
+}
+
+impl ExactSizeIterator for Squares {}
+
+impl IntoIterator for Bitboard {
+  type Item = u32;
+  type IntoIter = Squares;
+
+  fn into_iter(self) -> Squares {
+    self.squares()
+  }
+}
+
+impl FromIterator<u32> for Bitboard {
+  fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+    Bitboard(iter.into_iter().fold(0, |bb, sq| bb | 1 << sq))
+  }
+}
+
+macro_rules! bitboard_op {
+  ($trait:ident, $fn:ident, $assign_trait:ident, $assign_fn:ident, $op:tt) => {
+    impl $trait for Bitboard {
+      type Output = Bitboard;
+
+      #[inline(always)]
+      fn $fn(self, rhs: Bitboard) -> Bitboard {
+        Bitboard(self.0 $op rhs.0)
+      }
+    }
+
+    impl $assign_trait for Bitboard {
+      #[inline(always)]
+      fn $assign_fn(&mut self, rhs: Bitboard) {
+        self.0 = self.0 $op rhs.0;
+      }
+    }
+  };
+}
+
+bitboard_op!(BitAnd, bitand, BitAndAssign, bitand_assign, &);
+bitboard_op!(BitOr, bitor, BitOrAssign, bitor_assign, |);
+bitboard_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+impl Not for Bitboard {
+  type Output = Bitboard;
+
+  #[inline(always)]
+  fn not(self) -> Bitboard {
+    Bitboard(!self.0)
+  }
+}
+
+// prints the board with rank 8 at the top, like the GUI
+impl fmt::Debug for Bitboard {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "Bitboard({:#018x})", self.0)?;
+    for y in 0..8 {
+      for x in 0..8 {
+        let c = if self.contains(8 * y + x) { 'X' } else { '.' };
+        write!(f, "{c}")?;
+      }
+      writeln!(f)?;
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,804 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::Index;
+use std::str::FromStr;
+
+use crate::bitboard::Bitboard;
+use crate::movegen::{attackers, has_legal_move, is_in_check, legal_moves, to_coord, PROMO_OPTS};
+use crate::movelist::MoveList;
+use crate::piece::PieceType::*;
+use crate::piece::*;
+use crate::square::{square_name, ParseSquareError, Square};
+use crate::zobrist;
+
+type BoardState = Option<Piece>;
+
+// score of a checkmate, from white's point of view
+pub const MATE_SCORE: i32 = 1_000_000;
+
+#[derive(Copy, Clone, Debug)]
+pub struct Board {
+  // mailbox, so that indexing can hand out a reference to a square;
+  // always kept in sync with the bitboards below
+  pub(crate) board: [BoardState; 64],
+
+  // one bitboard per piece type (both colors) and one per color
+  pub(crate) pieces: [Bitboard; 6],
+  pub(crate) colors: [Bitboard; 2],
+
+  // outer-
+  // [0] - white,
+  // [1] - black
+
+  // inner [bool;2] ->
+  //  [0] - left
+  //  [1] - right (in terms of x-axis, not necessarily from player's perspective)
+  pub(crate) castling_rights: [[bool; 2]; 2],
+
+  // en passant
+  // square that just moved
+  // after apply_move, this should be set to the square of the pawn
+  // that is currently there.
+
+  // square that CAN BE CAPTURED (is technically empty)
+
+  // should be set to none after every turn
+  pub(crate) en_passant_square: Option<(u32, u32)>,
+
+  pub(crate) side_to_move: PieceColor,
+
+  // number of halfmoves since the last capture or pawn advance
+  // (for the fifty-move rule)
+  pub(crate) halfmove_clock: u32,
+
+  // starts at 1, incremented after black moves
+  pub(crate) fullmove_number: u32,
+
+  // Zobrist key of everything above except the move counters, kept up to
+  // date by put(), remove() and make_move()
+  pub(crate) hash: u64,
+}
+
+/// What kind of move a `Move` is. Everything `make_move` needs to know
+/// beyond the two squares is spelled out here, so the board never has to be
+/// inspected to tell a capture, castle or en passant apart.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MoveKind {
+  Quiet,
+  DoublePawnPush,
+  // castling towards the rook on the right/left (in terms of the x-axis),
+  // encoded as the king's move
+  KingsideCastle,
+  QueensideCastle,
+  Capture,
+  EnPassant,
+  Promotion(PieceType),
+  PromotionCapture(PieceType),
+}
+
+impl MoveKind {
+  // 4-bit encoding, the two high bits being "promotion" and "capture"; the
+  // promotion piece is the index into PROMO_OPTS
+  const fn to_bits(self) -> u16 {
+    match self {
+      MoveKind::Quiet => 0,
+      MoveKind::DoublePawnPush => 1,
+      MoveKind::KingsideCastle => 2,
+      MoveKind::QueensideCastle => 3,
+      MoveKind::Capture => 4,
+      MoveKind::EnPassant => 5,
+      MoveKind::Promotion(class) => 8 | promo_bits(class),
+      MoveKind::PromotionCapture(class) => 12 | promo_bits(class),
+    }
+  }
+
+  const fn from_bits(bits: u16) -> Self {
+    match bits {
+      0 => MoveKind::Quiet,
+      1 => MoveKind::DoublePawnPush,
+      2 => MoveKind::KingsideCastle,
+      3 => MoveKind::QueensideCastle,
+      4 => MoveKind::Capture,
+      5 => MoveKind::EnPassant,
+      8..=11 => MoveKind::Promotion(PROMO_OPTS[bits as usize & 3]),
+      12..=15 => MoveKind::PromotionCapture(PROMO_OPTS[bits as usize & 3]),
+      _ => unreachable!(),
+    }
+  }
+}
+
+const fn promo_bits(class: PieceType) -> u16 {
+  match class {
+    Knight => 0,
+    Bishop => 1,
+    Rook => 2,
+    Queen => 3,
+    Pawn | King => panic!("pawns can only promote to a knight, bishop, rook or queen"),
+  }
+}
+
+/// A move packed into 16 bits: origin square in bits 0-5, destination square
+/// in bits 6-11 (both `8 * y + x`) and the `MoveKind` in bits 12-15.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Move(u16);
+
+impl Move {
+  // placeholder for filling unused MoveList slots; a8 to a8 is never a
+  // real move
+  pub(crate) const NULL: Move = Move(0);
+
+  #[inline(always)]
+  pub const fn new(from: u32, to: u32, kind: MoveKind) -> Self {
+    debug_assert!(from < 64 && to < 64);
+    Move(from as u16 | (to as u16) << 6 | kind.to_bits() << 12)
+  }
+
+  #[inline]
+  pub const fn from_coords((x1, y1): (u32, u32), (x2, y2): (u32, u32), kind: MoveKind) -> Self {
+    Self::new(8 * y1 + x1, 8 * y2 + x2, kind)
+  }
+
+  /// Origin square index (`8 * y + x`).
+  #[inline(always)]
+  pub const fn from_sq(self) -> u32 {
+    (self.0 & 0x3f) as u32
+  }
+
+  /// Destination square index (`8 * y + x`).
+  #[inline(always)]
+  pub const fn to_sq(self) -> u32 {
+    ((self.0 >> 6) & 0x3f) as u32
+  }
+
+  #[inline]
+  pub const fn from(self) -> (u32, u32) {
+    to_coord(self.from_sq())
+  }
+
+  #[inline]
+  pub const fn to(self) -> (u32, u32) {
+    to_coord(self.to_sq())
+  }
+
+  #[inline]
+  pub const fn coords(self) -> ((u32, u32), (u32, u32)) {
+    (self.from(), self.to())
+  }
+
+  #[inline(always)]
+  pub const fn kind(self) -> MoveKind {
+    MoveKind::from_bits(self.0 >> 12)
+  }
+
+  /// Piece type a pawn promotes to, if this is a promotion.
+  #[inline]
+  pub const fn promotion(self) -> Option<PieceType> {
+    match self.kind() {
+      MoveKind::Promotion(class) | MoveKind::PromotionCapture(class) => Some(class),
+      _ => None,
+    }
+  }
+
+  /// Whether this move takes a piece (including en passant).
+  #[inline]
+  pub const fn is_capture(self) -> bool {
+    self.0 & (4 << 12) != 0
+  }
+
+  #[inline]
+  pub const fn is_castle(self) -> bool {
+    matches!(
+      self.kind(),
+      MoveKind::KingsideCastle | MoveKind::QueensideCastle
+    )
+  }
+}
+
+impl fmt::Debug for Move {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Move")
+      .field("from", &self.from())
+      .field("to", &self.to())
+      .field("kind", &self.kind())
+      .finish()
+  }
+}
+
+// long algebraic notation as used by UCI: origin and destination square,
+// plus the promotion piece in lowercase (e2e4, e1g1, e7e8q)
+impl fmt::Display for Move {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}{}", square_name(self.from()), square_name(self.to()))?;
+    if let Some(class) = self.promotion() {
+      let piece = Piece {
+        class,
+        color: PieceColor::Black,
+      };
+      write!(f, "{}", piece.to_char())?;
+    }
+    Ok(())
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseMoveError {
+  /// Not four or five characters long.
+  InvalidLength(String),
+  InvalidSquare(ParseSquareError),
+  /// The fifth character isn't one of `n`, `b`, `r`, `q`.
+  InvalidPromotion(String),
+}
+
+impl fmt::Display for ParseMoveError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ParseMoveError::InvalidLength(s) => write!(f, "invalid move length: '{s}'"),
+      ParseMoveError::InvalidSquare(err) => write!(f, "{err}"),
+      ParseMoveError::InvalidPromotion(s) => write!(f, "invalid promotion piece: '{s}'"),
+    }
+  }
+}
+
+impl Error for ParseMoveError {}
+
+impl From<ParseSquareError> for ParseMoveError {
+  fn from(err: ParseSquareError) -> Self {
+    ParseMoveError::InvalidSquare(err)
+  }
+}
+
+/// Parses long algebraic notation (e2e4, e7e8q). A string alone can't say
+/// whether a move is a capture, castles or en passant, so the result is a
+/// `Quiet` or `Promotion` move; `Board::try_apply` works out the rest from
+/// the position.
+impl FromStr for Move {
+  type Err = ParseMoveError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if !s.is_ascii() || !(4..=5).contains(&s.len()) {
+      return Err(ParseMoveError::InvalidLength(s.to_string()));
+    }
+
+    let from: Square = s[0..2].parse()?;
+    let to: Square = s[2..4].parse()?;
+
+    let kind = match &s[4..] {
+      "" => MoveKind::Quiet,
+      promo => match promo.chars().next().and_then(Piece::from_char) {
+        Some(Piece { class, .. }) if PROMO_OPTS.contains(&class) => MoveKind::Promotion(class),
+        _ => return Err(ParseMoveError::InvalidPromotion(promo.to_string())),
+      },
+    };
+
+    Ok(Move::new(from.index(), to.index(), kind))
+  }
+}
+
+/// Everything `make_move` changes that can't be recomputed from the move
+/// itself, so that `unmake_move` can restore the position exactly.
+#[derive(Copy, Clone, Debug)]
+pub struct Undo {
+  pub(crate) mv: Move,
+
+  // captured piece and the square it was on (which differs from the
+  // destination square for en passant)
+  pub(crate) captured: Option<(u32, Piece)>,
+
+  pub(crate) castling_rights: [[bool; 2]; 2],
+  pub(crate) en_passant_square: Option<(u32, u32)>,
+  pub(crate) halfmove_clock: u32,
+  pub(crate) fullmove_number: u32,
+  pub(crate) hash: u64,
+}
+
+impl Undo {
+  #[inline]
+  pub fn mv(&self) -> Move {
+    self.mv
+  }
+
+  #[inline]
+  pub fn captured(&self) -> Option<Piece> {
+    self.captured.map(|(_, p)| p)
+  }
+}
+
+impl Board {
+  // standard board setup
+  pub fn new() -> Self {
+    let mut board = [None; 64];
+
+    for j in 0..8 {
+      board[8 + j] = Some(BP);
+      board[8 * 6 + j] = Some(WP);
+    }
+
+    board[8 * 7] = Some(WR);
+    board[8 * 7 + 7] = Some(WR);
+    board[0] = Some(BR);
+    board[7] = Some(BR);
+    board[8 * 7 + 1] = Some(WN);
+    board[8 * 7 + 6] = Some(WN);
+    board[1] = Some(BN);
+    board[6] = Some(BN);
+    board[8 * 7 + 2] = Some(WB);
+    board[8 * 7 + 5] = Some(WB);
+    board[2] = Some(BB);
+    board[5] = Some(BB);
+    board[3] = Some(BQ);
+    board[8 * 7 + 3] = Some(WQ);
+    board[4] = Some(BK);
+    board[8 * 7 + 4] = Some(WK);
+
+    let mut board = Self {
+      castling_rights: [[true; 2]; 2],
+      ..Self::with_pieces(board)
+    };
+    board.hash = board.compute_hash();
+    board
+  }
+
+  // board with the given pieces, white to move, no castling rights and no
+  // en passant square; callers that change any of the other fields have to
+  // recompute the hash afterwards
+  pub(crate) fn with_pieces(squares: [BoardState; 64]) -> Self {
+    let mut board = Self {
+      board: [None; 64],
+      pieces: [Bitboard::EMPTY; 6],
+      colors: [Bitboard::EMPTY; 2],
+      castling_rights: [[false; 2]; 2],
+      en_passant_square: None,
+      side_to_move: PieceColor::White,
+      halfmove_clock: 0,
+      fullmove_number: 1,
+      hash: 0,
+    };
+
+    for (sq, piece) in squares.into_iter().enumerate() {
+      if let Some(piece) = piece {
+        board.put(sq as u32, piece);
+      }
+    }
+
+    board
+  }
+
+  // place a piece on an empty square
+  #[inline(always)]
+  fn put(&mut self, sq: u32, piece: Piece) {
+    debug_assert!(self.board[sq as usize].is_none());
+
+    let bb = Bitboard::from_square(sq);
+    self.board[sq as usize] = Some(piece);
+    self.pieces[piece.class as usize] |= bb;
+    self.colors[piece.color as usize] |= bb;
+    self.hash ^= zobrist::piece_key(piece, sq);
+  }
+
+  // take whatever is on a square off the board
+  #[inline(always)]
+  fn remove(&mut self, sq: u32) -> Option<Piece> {
+    let piece = self.board[sq as usize].take();
+
+    if let Some(p) = piece {
+      let bb = !Bitboard::from_square(sq);
+      self.pieces[p.class as usize] &= bb;
+      self.colors[p.color as usize] &= bb;
+      self.hash ^= zobrist::piece_key(p, sq);
+    }
+
+    piece
+  }
+
+  /// Replace the contents of a square. Does not touch castling rights or
+  /// any other state; meant for setting up positions and for display. The
+  /// hash follows, including whether the en passant square still counts.
+  pub fn set_piece(&mut self, (x, y): (u32, u32), piece: Option<Piece>) {
+    let sq = 8 * y + x;
+    // a pawn appearing or disappearing next to the en passant square can
+    // make a capture there possible or impossible
+    self.hash ^= zobrist::en_passant_key(self.en_passant_capture_square());
+    self.remove(sq);
+    if let Some(piece) = piece {
+      self.put(sq, piece);
+    }
+    self.hash ^= zobrist::en_passant_key(self.en_passant_capture_square());
+  }
+
+  #[inline(always)]
+  pub fn pieces(&self, class: PieceType, color: PieceColor) -> Bitboard {
+    self.pieces[class as usize] & self.colors[color as usize]
+  }
+
+  #[inline(always)]
+  pub fn color_pieces(&self, color: PieceColor) -> Bitboard {
+    self.colors[color as usize]
+  }
+
+  #[inline(always)]
+  pub fn occupied(&self) -> Bitboard {
+    self.colors[0] | self.colors[1]
+  }
+
+  // square index (8 * y + x) of the king of the given color
+  #[inline(always)]
+  pub fn king_square(&self, color: PieceColor) -> u32 {
+    self
+      .pieces(King, color)
+      .first()
+      .expect("king should always exist on board")
+  }
+
+  /// Pieces of color `by` that attack (x, y), as a set of squares.
+  #[inline]
+  pub fn attackers_to(&self, (x, y): (u32, u32), by: PieceColor) -> Bitboard {
+    attackers(self, 8 * y + x, by, self.occupied())
+  }
+
+  /// The en passant square if the side to move can legally capture there,
+  /// which is what makes it part of the position for the repetition rule
+  /// (FIDE 9.2.3). A double push past no enemy pawn, or only a pinned one,
+  /// leaves a position equal to the same one without the square.
+  pub fn en_passant_capture_square(&self) -> Option<(u32, u32)> {
+    let (x, y) = self.en_passant_square?;
+    let us = self.side_to_move;
+    let to = 8 * y + x;
+    // the pawn that double pushed stands just past the square
+    let captured = (to as i32 - 8 * us.direction()) as u32;
+    // set_piece can leave a side without a king, which nothing is pinned to
+    let king = self.pieces(King, us).first();
+
+    let capturers = self.attackers_to((x, y), us) & self.pieces(Pawn, us);
+    capturers
+      .into_iter()
+      .any(|from| {
+        // played out on the occupancy alone, since make_move needs this to
+        // hash the position
+        let occupied =
+          (self.occupied() & !Bitboard::from_square(from) & !Bitboard::from_square(captured))
+            | Bitboard::from_square(to);
+        king.is_none_or(|king| {
+          (attackers(self, king, !us, occupied) & !Bitboard::from_square(captured)).is_empty()
+        })
+      })
+      .then_some((x, y))
+  }
+
+  /// Whether any piece of color `by` attacks (x, y).
+  #[inline]
+  pub fn is_attacked(&self, square: (u32, u32), by: PieceColor) -> bool {
+    !self.attackers_to(square, by).is_empty()
+  }
+
+  /// Sliders of color `by` that attack (x, y) through other pieces attacking
+  /// it: the queen behind a rook on a file, the second rook of a doubled
+  /// pair, a bishop behind a pawn, and so on. These join in once the pieces
+  /// in front of them have captured. Pieces in front can be of either color,
+  /// and direct attackers are not included.
+  pub fn xray_attackers_to(&self, (x, y): (u32, u32), by: PieceColor) -> Bitboard {
+    let sq = 8 * y + x;
+    let mut occupied = self.occupied();
+    let both = |occupied| attackers(self, sq, by, occupied) | attackers(self, sq, !by, occupied);
+
+    let direct = both(occupied);
+    let mut seen = direct;
+    loop {
+      // take the attackers found so far off the board and see who was
+      // standing behind them
+      occupied &= !seen;
+      let revealed = both(occupied) & occupied & !seen;
+      if revealed.is_empty() {
+        break;
+      }
+      seen |= revealed;
+    }
+
+    seen & !direct & self.color_pieces(by)
+  }
+
+  /// Zobrist key of the position: positions that are the same for the
+  /// repetition rule (see [`Board::same_position`]) have equal keys.
+  #[inline]
+  pub fn hash(&self) -> u64 {
+    self.hash
+  }
+
+  // the key computed from scratch rather than incrementally
+  pub(crate) fn compute_hash(&self) -> u64 {
+    let pieces = self
+      .board
+      .iter()
+      .enumerate()
+      .filter_map(|(sq, p)| p.map(|p| zobrist::piece_key(p, sq as u32)))
+      .fold(0, |acc, key| acc ^ key);
+
+    pieces
+      ^ zobrist::side_key(self.side_to_move)
+      ^ zobrist::castling_key(self.castling_rights)
+      ^ zobrist::en_passant_key(self.en_passant_capture_square())
+  }
+
+  #[inline]
+  pub fn side_to_move(&self) -> PieceColor {
+    self.side_to_move
+  }
+
+  #[inline]
+  pub fn halfmove_clock(&self) -> u32 {
+    self.halfmove_clock
+  }
+
+  #[inline]
+  pub fn fullmove_number(&self) -> u32 {
+    self.fullmove_number
+  }
+
+  /// Get copy of board after applying a move.
+  pub fn apply_move(&self, mv: Move) -> Board {
+    let mut board = *self;
+    board.make_move(mv);
+    board
+  }
+
+  /// Play a move in place, returning what `unmake_move` needs to take it
+  /// back. The move has to be one generated for this position; nothing
+  /// about it is checked beyond a few debug assertions.
+  pub fn make_move(&mut self, mv: Move) -> Undo {
+    let (from, to) = (mv.from_sq(), mv.to_sq());
+
+    let mut undo = Undo {
+      mv,
+      captured: None,
+      castling_rights: self.castling_rights,
+      en_passant_square: self.en_passant_square,
+      halfmove_clock: self.halfmove_clock,
+      fullmove_number: self.fullmove_number,
+      hash: self.hash,
+    };
+
+    // castling rights and the en passant square are hashed back in once
+    // the move is done
+    self.hash ^= zobrist::castling_key(self.castling_rights)
+      ^ zobrist::en_passant_key(self.en_passant_capture_square())
+      ^ zobrist::side_key(self.side_to_move);
+
+    self.en_passant_square = None;
+
+    let piece = match self.remove(from) {
+      Some(piece) => piece,
+      None => unreachable!("starting square should not be empty in make_move()"),
+    };
+    let color = piece.color;
+
+    match mv.kind() {
+      MoveKind::Quiet => self.put(to, piece),
+      MoveKind::DoublePawnPush => {
+        debug_assert!(piece.is_pawn());
+        self.put(to, piece);
+        // the square the pawn skipped over
+        self.en_passant_square = Some(to_coord((from + to) / 2));
+      }
+      MoveKind::KingsideCastle | MoveKind::QueensideCastle => {
+        debug_assert!(piece.is_king());
+
+        // checks, attacked squares and pieces in between are validated by
+        // can_castle() before a castling move is ever generated or accepted
+        let (rook_from, rook_to) = castling_rook_squares(mv);
+        let rook = self.remove(rook_from);
+
+        // assert rook is there
+        debug_assert!(rook.map(|p| p.class == PieceType::Rook).unwrap_or(false));
+
+        self.put(to, piece);
+        if let Some(rook) = rook {
+          self.put(rook_to, rook);
+        }
+      }
+      MoveKind::Capture => {
+        undo.captured = self.remove(to).map(|p| (to, p));
+        self.put(to, piece);
+      }
+      MoveKind::EnPassant => {
+        // the captured pawn is behind the target square
+        let pawn_sq = (to as i32 - 8 * color.direction()) as u32;
+        let pawn_capture = self.remove(pawn_sq);
+        debug_assert_eq!(
+          pawn_capture,
+          Some(Piece {
+            class: PieceType::Pawn,
+            color: !color
+          })
+        );
+        undo.captured = pawn_capture.map(|p| (pawn_sq, p));
+        self.put(to, piece);
+      }
+      MoveKind::Promotion(class) => self.put(to, Piece { class, color }),
+      MoveKind::PromotionCapture(class) => {
+        undo.captured = self.remove(to).map(|p| (to, p));
+        self.put(to, Piece { class, color });
+      }
+    }
+
+    debug_assert_eq!(mv.is_capture(), undo.captured.is_some());
+
+    // moving the king or a rook off its home square, or capturing a rook on
+    // its home square, loses the corresponding castling right (otherwise a
+    // different rook could later arrive there and castle)
+    if self.castling_rights != [[false; 2]; 2] {
+      if piece.is_king() {
+        self.castling_rights[color as usize] = [false; 2];
+      }
+      for sq in [from, to] {
+        if let Some((color, side)) = castling_corner(sq) {
+          self.castling_rights[color as usize][side] = false;
+        }
+      }
+    }
+
+    // pawn moves and captures reset the fifty-move counter
+    if piece.is_pawn() || mv.is_capture() {
+      self.halfmove_clock = 0;
+    } else {
+      self.halfmove_clock += 1;
+    }
+    if color.is_black() {
+      self.fullmove_number += 1;
+    }
+    self.side_to_move = !color;
+
+    self.hash ^= zobrist::castling_key(self.castling_rights)
+      ^ zobrist::en_passant_key(self.en_passant_capture_square())
+      ^ zobrist::side_key(self.side_to_move);
+
+    undo
+  }
+
+  /// Take back the move `undo` was returned for. Moves have to be unmade in
+  /// the reverse order they were made in.
+  pub fn unmake_move(&mut self, undo: Undo) {
+    let mv = undo.mv;
+    let (from, to) = (mv.from_sq(), mv.to_sq());
+
+    let color = !self.side_to_move;
+
+    let piece = match self.remove(to) {
+      Some(piece) => piece,
+      None => unreachable!("destination square should not be empty in unmake_move()"),
+    };
+    debug_assert_eq!(piece.color, color);
+
+    if mv.promotion().is_some() {
+      self.put(
+        from,
+        Piece {
+          class: PieceType::Pawn,
+          color,
+        },
+      );
+    } else {
+      self.put(from, piece);
+    }
+
+    // put the rook back in its corner
+    if mv.is_castle() {
+      let (rook_from, rook_to) = castling_rook_squares(mv);
+      if let Some(rook) = self.remove(rook_to) {
+        self.put(rook_from, rook);
+      }
+    }
+
+    if let Some((sq, captured)) = undo.captured {
+      self.put(sq, captured);
+    }
+
+    self.castling_rights = undo.castling_rights;
+    self.en_passant_square = undo.en_passant_square;
+    self.halfmove_clock = undo.halfmove_clock;
+    self.fullmove_number = undo.fullmove_number;
+    self.side_to_move = color;
+    self.hash = undo.hash;
+  }
+
+  // very simple evaluation, just sum up piece values
+  // positive = advantage for white, negative = advantage for black
+  pub fn eval(&self) -> i32 {
+    let to_move = self.side_to_move;
+    if !has_legal_move(self) {
+      return if !is_in_check(self, to_move) {
+        // stalemate
+        0
+      } else if to_move.is_white() {
+        -MATE_SCORE
+      } else {
+        MATE_SCORE
+      };
+    }
+
+    self.eval_pieces()
+  }
+
+  // eval without the checkmate and stalemate test, for when the caller
+  // already knows the side to move has a move
+  pub(crate) fn eval_pieces(&self) -> i32 {
+    self
+      .board
+      .iter()
+      .enumerate()
+      .map(|(idx, square)| match square {
+        Some(p) => {
+          let mult = if p.color.is_white() { 1 } else { -1 };
+
+          let table = match p.class {
+            Pawn => &PAWN_TABLE,
+            Knight => &KNIGHT_TABLE,
+            Bishop => &BISHOP_TABLE,
+            Rook => &ROOK_TABLE,
+            Queen => &QUEEN_TABLE,
+            King => &KING_TABLE,
+          };
+
+          let (x, y) = to_coord(idx as u32);
+          let y = if p.color.is_white() { y } else { 7 - y };
+
+          mult * (p.class.value() + table[(y * 8 + x) as usize])
+        }
+        None => 0,
+      })
+      .sum()
+  }
+
+  /// All legal moves for the side to move.
+  pub fn moves_for_player(&self) -> MoveList {
+    legal_moves(self)
+  }
+}
+
+// where the rook starts and ends up for a castling move
+#[inline]
+fn castling_rook_squares(mv: Move) -> (u32, u32) {
+  let from = mv.from_sq();
+  if mv.kind() == MoveKind::KingsideCastle {
+    (from + 3, from + 1)
+  } else {
+    (from - 4, from - 1)
+  }
+}
+
+// the castling right (color, left/right) tied to a rook's home square
+#[inline]
+fn castling_corner(sq: u32) -> Option<(PieceColor, usize)> {
+  match sq {
+    0 => Some((PieceColor::Black, 0)),
+    7 => Some((PieceColor::Black, 1)),
+    56 => Some((PieceColor::White, 0)),
+    63 => Some((PieceColor::White, 1)),
+    _ => None,
+  }
+}
+
+impl Default for Board {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Index<usize> for Board {
+  type Output = Option<Piece>;
+  fn index(&self, index: usize) -> &Self::Output {
+    &self.board[index]
+  }
+}
+
+impl Index<u32> for Board {
+  type Output = Option<Piece>;
+  fn index(&self, index: u32) -> &Self::Output {
+    &self.board[index as usize]
+  }
+}
+
+impl<T: Into<u32>> Index<(T, T)> for Board {
+  type Output = Option<Piece>;
+  fn index(&self, (x, y): (T, T)) -> &Self::Output {
+    &self.board[8 * y.into() as usize + x.into() as usize]
+  }
+}
@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::board::{Board, Move};
+use crate::pgn::Game;
+use crate::polyglot::{decode_move, encode_move, polyglot_key};
+use crate::status::GameResult;
+
+/// First bytes of every book file. The last one is the format's version,
+/// bumped whenever the keys or the move encoding change: version 1 keyed
+/// positions by [`Board::hash`], which is free to change between releases.
+pub const BOOK_MAGIC: &[u8; 8] = b"CHESSBK2";
+
+// what every version's magic starts with
+const MAGIC_PREFIX: &[u8; 7] = b"CHESSBK";
+
+const ENTRY_SIZE: usize = 16;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Entry {
+  key: u64,
+  mv: u16,
+  weight: u16,
+}
+
+/// An opening book, in this crate's native format or read from a Polyglot
+/// `.bin` file (see [`Book::load`]).
+///
+/// A native book is the 8-byte [`BOOK_MAGIC`] followed by 16-byte
+/// big-endian entries sorted by key, each a `u64` key, a `u16` move, a
+/// `u16` weight and 4 reserved bytes. Keys and moves are those of
+/// [`crate::polyglot`], which are fixed by a published table rather than by
+/// anything in this crate, so a book stays valid across releases.
+#[derive(Clone, Debug, Default)]
+pub struct Book {
+  // sorted by key, then heaviest first
+  entries: Vec<Entry>,
+  // read from a Polyglot file, and written back as one
+  polyglot: bool,
+}
+
+#[derive(Debug)]
+pub enum BookError {
+  Io(io::Error),
+  /// The file doesn't start with [`BOOK_MAGIC`].
+  NotABook,
+  /// The file is a book, but in another version of the format.
+  UnsupportedVersion(u8),
+  /// The file ends partway through an entry.
+  Truncated,
+}
+
+impl fmt::Display for BookError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      BookError::Io(err) => err.fmt(f),
+      BookError::NotABook => f.write_str("not an opening book"),
+      BookError::UnsupportedVersion(version) => write!(
+        f,
+        "book format version '{}' is not supported, rebuild the book",
+        *version as char
+      ),
+      BookError::Truncated => f.write_str("book file is truncated"),
+    }
+  }
+}
+
+impl Error for BookError {}
+
+impl From<io::Error> for BookError {
+  fn from(err: io::Error) -> Self {
+    BookError::Io(err)
+  }
+}
+
+// 16-byte big-endian entries, sorted by key then weight
+fn read_entries(body: &[u8]) -> Result<Vec<Entry>, BookError> {
+  if !body.len().is_multiple_of(ENTRY_SIZE) {
+    return Err(BookError::Truncated);
+  }
+
+  let mut entries: Vec<Entry> = body
+    .chunks_exact(ENTRY_SIZE)
+    .map(|e| Entry {
+      key: u64::from_be_bytes(e[0..8].try_into().unwrap()),
+      mv: u16::from_be_bytes([e[8], e[9]]),
+      weight: u16::from_be_bytes([e[10], e[11]]),
+    })
+    .collect();
+  // don't rely on whoever wrote the file to have sorted it
+  entries.sort_by(|a, b| {
+    a.key
+      .cmp(&b.key)
+      .then(b.weight.cmp(&a.weight))
+      .then(a.mv.cmp(&b.mv))
+  });
+  Ok(entries)
+}
+
+impl Book {
+  /// A book in the native format, starting with [`BOOK_MAGIC`].
+  pub fn from_bytes(bytes: &[u8]) -> Result<Book, BookError> {
+    let body = match bytes.strip_prefix(MAGIC_PREFIX) {
+      Some([version, body @ ..]) if *version == BOOK_MAGIC[7] => body,
+      Some([version, ..]) => return Err(BookError::UnsupportedVersion(*version)),
+      _ => return Err(BookError::NotABook),
+    };
+    Ok(Book {
+      entries: read_entries(body)?,
+      polyglot: false,
+    })
+  }
+
+  /// A Polyglot book, which is nothing but its entries.
+  pub fn from_polyglot_bytes(bytes: &[u8]) -> Result<Book, BookError> {
+    Ok(Book {
+      entries: read_entries(bytes)?,
+      polyglot: true,
+    })
+  }
+
+  /// The book in the format it was read in: native books start with
+  /// [`BOOK_MAGIC`], Polyglot books don't.
+  pub fn to_bytes(&self) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(BOOK_MAGIC.len() + ENTRY_SIZE * self.entries.len());
+    if !self.polyglot {
+      bytes.extend_from_slice(BOOK_MAGIC);
+    }
+    for entry in &self.entries {
+      bytes.extend_from_slice(&entry.key.to_be_bytes());
+      bytes.extend_from_slice(&entry.mv.to_be_bytes());
+      bytes.extend_from_slice(&entry.weight.to_be_bytes());
+      bytes.extend_from_slice(&[0; 4]);
+    }
+    bytes
+  }
+
+  /// Read a book in either format: a file starting with a [`BOOK_MAGIC`] of
+  /// any version is a native book, anything else is taken for a Polyglot
+  /// one.
+  pub fn load(path: impl AsRef<Path>) -> Result<Book, BookError> {
+    let bytes = fs::read(path)?;
+    if bytes.starts_with(MAGIC_PREFIX) {
+      Book::from_bytes(&bytes)
+    } else {
+      Book::from_polyglot_bytes(&bytes)
+    }
+  }
+
+  pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+    fs::write(path, self.to_bytes())
+  }
+
+  /// Number of entries, i.e. of (position, move) pairs.
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+
+  /// The book moves for `board` with their weights, heaviest first.
+  /// Entries that aren't legal moves in the position are left out.
+  pub fn moves(&self, board: &Board) -> Vec<(Move, u16)> {
+    let key = polyglot_key(board);
+    let start = self.entries.partition_point(|e| e.key < key);
+
+    self.entries[start..]
+      .iter()
+      .take_while(|e| e.key == key)
+      .filter_map(|e| Some((decode_move(board, e.mv)?, e.weight)))
+      .collect()
+  }
+
+  /// A book move for `board`, chosen with probability proportional to its
+  /// weight. `random` is any uniformly distributed number; the same value
+  /// picks the same move.
+  pub fn pick(&self, board: &Board, random: u64) -> Option<Move> {
+    let moves = self.moves(board);
+    let total: u64 = moves.iter().map(|&(_, weight)| weight as u64).sum();
+    if total == 0 {
+      return None;
+    }
+
+    let mut target = random % total;
+    for (mv, weight) in moves {
+      if target < weight as u64 {
+        return Some(mv);
+      }
+      target -= weight as u64;
+    }
+    unreachable!()
+  }
+}
+
+/// Collects the moves played in a set of games into a [`Book`].
+///
+/// Every move within the first `max_ply` plies of a game scores 2 for the
+/// side that played it if that side won, 1 for a draw or an unfinished game
+/// and 0 for a loss. A move's weight is its total score over all games, so
+/// moves that only ever lost are left out of the book.
+#[derive(Clone, Debug)]
+pub struct BookBuilder {
+  max_ply: usize,
+  // (key, move) -> total score
+  scores: HashMap<(u64, u16), u64>,
+  games: usize,
+}
+
+impl BookBuilder {
+  pub fn new(max_ply: usize) -> Self {
+    BookBuilder {
+      max_ply,
+      scores: HashMap::new(),
+      games: 0,
+    }
+  }
+
+  /// Number of games added so far.
+  pub fn games(&self) -> usize {
+    self.games
+  }
+
+  pub fn add_game(&mut self, game: &Game) {
+    let result = game.result();
+
+    for (&mv, board) in game.moves().iter().zip(game.positions()).take(self.max_ply) {
+      let score = match result {
+        Some(GameResult::Draw) | None => 1,
+        Some(winner) if winner == GameResult::win_for(board.side_to_move()) => 2,
+        Some(_) => 0,
+      };
+      *self
+        .scores
+        .entry((polyglot_key(board), encode_move(mv)))
+        .or_insert(0) += score;
+    }
+
+    self.games += 1;
+  }
+
+  pub fn build(&self) -> Book {
+    let mut by_key: HashMap<u64, Vec<(u16, u64)>> = HashMap::new();
+    for (&(key, mv), &score) in &self.scores {
+      if score > 0 {
+        by_key.entry(key).or_default().push((mv, score));
+      }
+    }
+
+    let mut entries = Vec::with_capacity(self.scores.len());
+    for (key, moves) in by_key {
+      // weights are 16 bits, so scale down positions that have seen a lot
+      // of games, keeping every move in
+      let max = moves.iter().map(|&(_, score)| score).max().unwrap();
+      let divisor = max.div_ceil(u16::MAX as u64);
+      for (mv, score) in moves {
+        let weight = (score / divisor).max(1) as u16;
+        entries.push(Entry { key, mv, weight });
+      }
+    }
+
+    entries.sort_by(|a, b| {
+      a.key
+        .cmp(&b.key)
+        .then(b.weight.cmp(&a.weight))
+        .then(a.mv.cmp(&b.mv))
+    });
+    Book {
+      entries,
+      polyglot: false,
+    }
+  }
+}
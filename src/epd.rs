@@ -0,0 +1,202 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::board::{Board, Move};
+use crate::fen::FenError;
+use crate::san::SanError;
+
+/// One line of an EPD test suite: a position (the first four FEN fields)
+/// followed by operations such as `bm Qxf7+; id "WAC.001";`.
+///
+/// The operations test suites use are picked out; all of them, these
+/// included, are also kept in `operations` in the order they appear.
+#[derive(Clone, Debug)]
+pub struct Epd {
+  pub board: Board,
+  /// `id`: name of the position.
+  pub id: Option<String>,
+  /// `c0`: primary comment.
+  pub comment: Option<String>,
+  /// `bm`: any of these counts as solving the position.
+  pub best_moves: Vec<Move>,
+  /// `am`: playing any of these fails the position.
+  pub avoid_moves: Vec<Move>,
+  /// Every operation as an opcode and its operands, quotes removed.
+  pub operations: Vec<(String, Vec<String>)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EpdError {
+  InvalidFen(FenError),
+  /// An operation without its closing `;`, an unclosed string, or an
+  /// operation without an opcode.
+  Syntax(String),
+  /// A move in `bm` or `am` that isn't legal in the position.
+  IllegalMove {
+    opcode: String,
+    error: SanError,
+  },
+  /// An operand of the wrong form, like a non-numeric `hmvc`.
+  InvalidOperand {
+    opcode: String,
+    operand: String,
+  },
+}
+
+impl fmt::Display for EpdError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      EpdError::InvalidFen(err) => err.fmt(f),
+      EpdError::Syntax(text) => write!(f, "invalid EPD operation: '{text}'"),
+      EpdError::IllegalMove { opcode, error } => write!(f, "{opcode}: {error}"),
+      EpdError::InvalidOperand { opcode, operand } => {
+        write!(f, "invalid operand for {opcode}: '{operand}'")
+      }
+    }
+  }
+}
+
+impl Error for EpdError {}
+
+impl Epd {
+  /// Whether playing `mv` solves the position: it has to be one of the best
+  /// moves, if any are given, and none of the moves to avoid.
+  pub fn is_solved_by(&self, mv: Move) -> bool {
+    (self.best_moves.is_empty() || self.best_moves.contains(&mv)) && !self.avoid_moves.contains(&mv)
+  }
+
+  /// Operands of the first operation with this opcode.
+  pub fn operation(&self, opcode: &str) -> Option<&[String]> {
+    self
+      .operations
+      .iter()
+      .find(|(op, _)| op == opcode)
+      .map(|(_, operands)| operands.as_slice())
+  }
+}
+
+// split the operations part of a line into opcode and operands
+fn parse_operations(text: &str) -> Result<Vec<(String, Vec<String>)>, EpdError> {
+  let mut operations = Vec::new();
+  let mut words = Vec::new();
+  let mut chars = text.chars().peekable();
+
+  loop {
+    match chars.next() {
+      None => break,
+      Some(c) if c.is_whitespace() => {}
+      Some(';') => {
+        if words.is_empty() {
+          return Err(EpdError::Syntax(";".to_string()));
+        }
+        let opcode = words.remove(0);
+        operations.push((opcode, std::mem::take(&mut words)));
+      }
+      Some('"') => {
+        let mut word = String::new();
+        loop {
+          match chars.next() {
+            Some('"') => break,
+            Some(c) => word.push(c),
+            None => return Err(EpdError::Syntax(format!("\"{word}"))),
+          }
+        }
+        words.push(word);
+      }
+      Some(c) => {
+        let mut word = c.to_string();
+        while let Some(&c) = chars.peek().filter(|&&c| !c.is_whitespace() && c != ';') {
+          word.push(c);
+          chars.next();
+        }
+        words.push(word);
+      }
+    }
+  }
+
+  if !words.is_empty() {
+    return Err(EpdError::Syntax(words.join(" ")));
+  }
+
+  Ok(operations)
+}
+
+impl FromStr for Epd {
+  type Err = EpdError;
+
+  fn from_str(line: &str) -> Result<Self, Self::Err> {
+    let line = line.trim();
+
+    // the position is the first four whitespace-separated fields
+    let mut rest = line;
+    let mut fields = Vec::with_capacity(4);
+    for _ in 0..4 {
+      rest = rest.trim_start();
+      let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+      if end > 0 {
+        fields.push(&rest[..end]);
+      }
+      rest = &rest[end..];
+    }
+
+    let mut board = Board::from_fen(&fields.join(" ")).map_err(EpdError::InvalidFen)?;
+    let operations = parse_operations(rest)?;
+
+    let mut epd = Epd {
+      board,
+      id: None,
+      comment: None,
+      best_moves: Vec::new(),
+      avoid_moves: Vec::new(),
+      operations: Vec::new(),
+    };
+
+    for (opcode, operands) in &operations {
+      let first = || operands.first().cloned();
+      let number = |operand: &String| {
+        operand
+          .parse::<u32>()
+          .map_err(|_| EpdError::InvalidOperand {
+            opcode: opcode.clone(),
+            operand: operand.clone(),
+          })
+      };
+
+      match opcode.as_str() {
+        "id" => epd.id = first(),
+        "c0" => epd.comment = first(),
+        "bm" | "am" => {
+          let moves = operands
+            .iter()
+            .map(|san| board.parse_san(san))
+            .collect::<Result<Vec<Move>, SanError>>()
+            .map_err(|error| EpdError::IllegalMove {
+              opcode: opcode.clone(),
+              error,
+            })?;
+          if opcode == "bm" {
+            epd.best_moves = moves;
+          } else {
+            epd.avoid_moves = moves;
+          }
+        }
+        "hmvc" => {
+          if let Some(operand) = operands.first() {
+            board.halfmove_clock = number(operand)?;
+          }
+        }
+        "fmvn" => {
+          if let Some(operand) = operands.first() {
+            board.fullmove_number = number(operand)?;
+          }
+        }
+        _ => {}
+      }
+    }
+
+    epd.board = board;
+    epd.operations = operations;
+    Ok(epd)
+  }
+}
@@ -0,0 +1,307 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::board::Board;
+use crate::movegen::is_in_check;
+use crate::piece::*;
+use crate::square::{parse_square, square_name};
+
+pub const STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+/// The six space-separated fields of a FEN record, in order.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FenField {
+  Placement,
+  SideToMove,
+  Castling,
+  EnPassant,
+  HalfmoveClock,
+  FullmoveNumber,
+}
+
+impl fmt::Display for FenField {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      FenField::Placement => "piece placement",
+      FenField::SideToMove => "side to move",
+      FenField::Castling => "castling availability",
+      FenField::EnPassant => "en passant square",
+      FenField::HalfmoveClock => "halfmove clock",
+      FenField::FullmoveNumber => "fullmove number",
+    })
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FenError {
+  /// One of the first four fields is absent.
+  MissingField(FenField),
+  /// There is text after the fullmove number.
+  TrailingInput(String),
+  /// A field is present but its contents are invalid.
+  InvalidField { field: FenField, value: String },
+}
+
+impl FenError {
+  /// The field that failed to parse, if the error is about a specific field.
+  pub fn field(&self) -> Option<FenField> {
+    match self {
+      FenError::MissingField(field) | FenError::InvalidField { field, .. } => Some(*field),
+      FenError::TrailingInput(_) => None,
+    }
+  }
+
+  fn invalid(field: FenField, value: &str) -> Self {
+    FenError::InvalidField {
+      field,
+      value: value.to_string(),
+    }
+  }
+}
+
+impl fmt::Display for FenError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      FenError::MissingField(field) => write!(f, "FEN is missing the {field} field"),
+      FenError::TrailingInput(rest) => write!(f, "unexpected trailing input in FEN: '{rest}'"),
+      FenError::InvalidField { field, value } => write!(f, "invalid {field} in FEN: '{value}'"),
+    }
+  }
+}
+
+impl Error for FenError {}
+
+fn parse_placement(field: &str) -> Option<[Option<Piece>; 64]> {
+  let mut board = [None; 64];
+
+  let ranks: Vec<&str> = field.split('/').collect();
+  if ranks.len() != 8 {
+    return None;
+  }
+
+  // FEN lists ranks from 8 down to 1, which is the same order as our y-axis
+  for (y, rank) in ranks.into_iter().enumerate() {
+    let mut x = 0;
+    for c in rank.chars() {
+      if let Some(skip) = c.to_digit(10) {
+        if !(1..=8).contains(&skip) {
+          return None;
+        }
+        x += skip as usize;
+      } else {
+        if x >= 8 {
+          return None;
+        }
+        board[8 * y + x] = Some(Piece::from_char(c)?);
+        x += 1;
+      }
+
+      if x > 8 {
+        return None;
+      }
+    }
+
+    if x != 8 {
+      return None;
+    }
+  }
+
+  // pawns on the first or last rank would move off the board
+  let back_ranks = board[..8].iter().chain(&board[56..]);
+  if back_ranks.flatten().any(|p| p.class == PieceType::Pawn) {
+    return None;
+  }
+
+  // the rest of the engine assumes both kings are always on the board
+  for king in [WK, BK] {
+    if board.iter().filter(|&&p| p == Some(king)).count() != 1 {
+      return None;
+    }
+  }
+
+  Some(board)
+}
+
+impl Board {
+  /// Parse a position from Forsyth–Edwards Notation.
+  ///
+  /// The halfmove clock and fullmove number may be omitted (as in EPD), in
+  /// which case they default to 0 and 1.
+  pub fn from_fen(fen: &str) -> Result<Board, FenError> {
+    let mut fields = fen.split_whitespace();
+    let mut next = |field| fields.next().ok_or(FenError::MissingField(field));
+
+    let placement = next(FenField::Placement)?;
+    let side = next(FenField::SideToMove)?;
+    let castling = next(FenField::Castling)?;
+    let en_passant = next(FenField::EnPassant)?;
+    let halfmove = fields.next();
+    let fullmove = fields.next();
+
+    let rest: Vec<&str> = fields.collect();
+    if !rest.is_empty() {
+      return Err(FenError::TrailingInput(rest.join(" ")));
+    }
+
+    let board = parse_placement(placement)
+      .ok_or_else(|| FenError::invalid(FenField::Placement, placement))?;
+
+    let side_to_move = match side {
+      "w" => PieceColor::White,
+      "b" => PieceColor::Black,
+      _ => return Err(FenError::invalid(FenField::SideToMove, side)),
+    };
+
+    let mut castling_rights = [[false; 2]; 2];
+    if castling != "-" {
+      for c in castling.chars() {
+        let (color, side) = match c {
+          'K' => (PieceColor::White, 1),
+          'Q' => (PieceColor::White, 0),
+          'k' => (PieceColor::Black, 1),
+          'q' => (PieceColor::Black, 0),
+          _ => return Err(FenError::invalid(FenField::Castling, castling)),
+        };
+
+        // the king and rook have to still be on their home squares,
+        // otherwise move generation would castle with pieces that aren't there
+        let rank = if color.is_white() { 7 } else { 0 };
+        let rook_x = if side == 1 { 7 } else { 0 };
+        let home = |x: usize, class| board[8 * rank + x] == Some(Piece { class, color });
+
+        let right = &mut castling_rights[color as usize][side];
+        if *right || !home(4, PieceType::King) || !home(rook_x, PieceType::Rook) {
+          return Err(FenError::invalid(FenField::Castling, castling));
+        }
+        *right = true;
+      }
+    }
+
+    let en_passant_square = if en_passant == "-" {
+      None
+    } else {
+      // the target square must be on the 3rd rank (after a white double push)
+      // or the 6th rank (after a black double push), matching the side to move,
+      // with the pawn that just moved in front of it and the squares it
+      // passed over empty
+      let (expected_y, forward) = if side_to_move.is_white() {
+        (2, 1)
+      } else {
+        (5, -1)
+      };
+      let pawn = Piece {
+        class: PieceType::Pawn,
+        color: !side_to_move,
+      };
+      let at = |x: u32, y: u32| board[(8 * y + x) as usize];
+      match parse_square(en_passant) {
+        Some((x, y))
+          if y == expected_y
+            && at(x, y).is_none()
+            && at(x, (y as i32 - forward) as u32).is_none()
+            && at(x, (y as i32 + forward) as u32) == Some(pawn) =>
+        {
+          Some((x, y))
+        }
+        _ => return Err(FenError::invalid(FenField::EnPassant, en_passant)),
+      }
+    };
+
+    let halfmove_clock = match halfmove {
+      Some(s) => s
+        .parse()
+        .map_err(|_| FenError::invalid(FenField::HalfmoveClock, s))?,
+      None => 0,
+    };
+
+    let fullmove_number = match fullmove {
+      Some(s) => match s.parse() {
+        Ok(n) if n >= 1 => n,
+        _ => return Err(FenError::invalid(FenField::FullmoveNumber, s)),
+      },
+      None => 1,
+    };
+
+    let mut board = Board {
+      castling_rights,
+      en_passant_square,
+      side_to_move,
+      halfmove_clock,
+      fullmove_number,
+      ..Board::with_pieces(board)
+    };
+    board.hash = board.compute_hash();
+
+    // the side that just moved can't have left its king in check; search
+    // would go on to capture it
+    if is_in_check(&board, !side_to_move) {
+      return Err(FenError::invalid(FenField::SideToMove, side));
+    }
+
+    Ok(board)
+  }
+
+  /// Write the position in Forsyth–Edwards Notation.
+  pub fn to_fen(&self) -> String {
+    let mut fen = String::new();
+
+    for y in 0..8u32 {
+      let mut empty = 0;
+      for x in 0..8u32 {
+        match self[(x, y)] {
+          Some(p) => {
+            if empty > 0 {
+              fen.push(char::from_digit(empty, 10).unwrap());
+              empty = 0;
+            }
+            fen.push(p.to_char());
+          }
+          None => empty += 1,
+        }
+      }
+      if empty > 0 {
+        fen.push(char::from_digit(empty, 10).unwrap());
+      }
+      if y != 7 {
+        fen.push('/');
+      }
+    }
+
+    fen.push(' ');
+    fen.push(if self.side_to_move.is_white() {
+      'w'
+    } else {
+      'b'
+    });
+
+    fen.push(' ');
+    let rights = self.castling_rights;
+    let len_before = fen.len();
+    for (right, c) in [
+      (rights[0][1], 'K'),
+      (rights[0][0], 'Q'),
+      (rights[1][1], 'k'),
+      (rights[1][0], 'q'),
+    ] {
+      if right {
+        fen.push(c);
+      }
+    }
+    if fen.len() == len_before {
+      fen.push('-');
+    }
+
+    fen.push(' ');
+    match self.en_passant_square {
+      Some(sq) => fen.push_str(&square_name(sq)),
+      None => fen.push('-'),
+    }
+
+    fen.push_str(&format!(
+      " {} {}",
+      self.halfmove_clock, self.fullmove_number
+    ));
+
+    fen
+  }
+}
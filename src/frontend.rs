@@ -0,0 +1,96 @@
+//! What the GUI and the terminal front end share: the files the engine plays
+//! from, loading them, reporting tablebase results and varying the choice of
+//! book moves. Messages go to stdout, tagged `[Info]`, `[Error]` or
+//! `[Tablebase]`.
+
+use std::io::ErrorKind;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::board::Board;
+use crate::book::{Book, BookError};
+use crate::tablebase::{Outcome, Tablebase, TablebaseError};
+
+/// Opening book the engine plays from before it starts searching, if there
+/// is one (see the `book` tool); a Polyglot book works too.
+pub const BOOK_PATH: &str = "book.bin";
+
+/// Endgame tables the engine plays perfectly from, if there are any (see
+/// the `tablebase` tool).
+pub const TABLEBASE_DIR: &str = "tablebases";
+
+/// The book at `path`, or an empty one if there's no such file or it can't
+/// be read (which is reported).
+pub fn load_book(path: impl AsRef<Path>) -> Book {
+  let path = path.as_ref();
+  match Book::load(path) {
+    Ok(book) => {
+      println!(
+        "[Info] Loaded {} book entries from {}",
+        book.len(),
+        path.display()
+      );
+      book
+    }
+    Err(BookError::Io(err)) if err.kind() == ErrorKind::NotFound => Book::default(),
+    Err(err) => {
+      println!("[Error] Could not load {}: {}", path.display(), err);
+      Book::default()
+    }
+  }
+}
+
+/// The tables in `dir`, or none if there's no such directory or it can't
+/// be read (which is reported).
+pub fn load_tablebase(dir: impl AsRef<Path>) -> Tablebase {
+  let dir = dir.as_ref();
+  match Tablebase::load_dir(dir) {
+    Ok(tablebase) => {
+      println!(
+        "[Info] Loaded tables {} from {}",
+        tablebase.tables().join(", "),
+        dir.display()
+      );
+      tablebase
+    }
+    Err(TablebaseError::Io(err)) if err.kind() == ErrorKind::NotFound => Tablebase::new(),
+    Err(err) => {
+      println!("[Error] Could not load {}: {}", dir.display(), err);
+      Tablebase::new()
+    }
+  }
+}
+
+/// Announce `outcome`, the tablebase result of `board`, in moves of the
+/// winning side. Nothing is printed once the side to move is mated.
+pub fn print_outcome(board: &Board, outcome: Outcome) {
+  let (side, other) = if board.side_to_move().is_white() {
+    ("White", "Black")
+  } else {
+    ("Black", "White")
+  };
+  // plies to moves of the winning side
+  match outcome {
+    Outcome::Win(plies) => println!("[Tablebase] {} mates in {}", side, plies.div_ceil(2)),
+    Outcome::Draw => println!("[Tablebase] Draw"),
+    Outcome::Loss(0) => {}
+    Outcome::Loss(plies) => println!("[Tablebase] {} mates in {}", other, plies / 2),
+  }
+}
+
+/// A nonzero seed for [`next_random`], from the clock.
+pub fn random_seed() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map_or(0, |d| d.as_nanos() as u64)
+    | 1
+}
+
+/// Advance the xorshift64 `state` and return it. Only meant to vary the
+/// choice of book moves, not for anything that needs good randomness.
+pub fn next_random(state: &mut u64) -> u64 {
+  *state ^= *state << 13;
+  *state ^= *state >> 7;
+  *state ^= *state << 17;
+  *state
+}
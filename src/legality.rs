@@ -0,0 +1,132 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::attacks::piece_attacks;
+use crate::bitboard::Bitboard;
+use crate::board::{Board, Move};
+use crate::movegen::{leaves_king_safe, moves_for_piece};
+use crate::piece::*;
+use crate::square::square_name;
+
+/// Why `Board::try_apply` rejected a move.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IllegalMoveError {
+  /// The starting square is empty.
+  NoPiece { square: (u32, u32) },
+  /// The piece on the starting square belongs to the side not to move.
+  WrongColor { square: (u32, u32) },
+  /// The piece can't move that way, even on an empty board.
+  InvalidMovement,
+  /// A piece is in the way, or the destination holds one of the mover's
+  /// own pieces (or, for a pawn push, any piece).
+  Blocked,
+  /// Castling isn't possible: the right has been lost, there are pieces
+  /// between king and rook, or the king is in, passes through or lands in
+  /// check.
+  CastlingNotAllowed,
+  /// The move would leave the mover's own king in check.
+  LeavesKingInCheck,
+  /// A pawn reaching the last rank without a promotion piece, or a
+  /// promotion piece given for a move that isn't a promotion.
+  InvalidPromotion,
+}
+
+impl fmt::Display for IllegalMoveError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      IllegalMoveError::NoPiece { square } => write!(f, "no piece on {}", square_name(*square)),
+      IllegalMoveError::WrongColor { square } => {
+        write!(
+          f,
+          "the piece on {} is not the side to move's",
+          square_name(*square)
+        )
+      }
+      IllegalMoveError::InvalidMovement => f.write_str("the piece can't move like that"),
+      IllegalMoveError::Blocked => f.write_str("the move is blocked"),
+      IllegalMoveError::CastlingNotAllowed => f.write_str("castling is not allowed here"),
+      IllegalMoveError::LeavesKingInCheck => f.write_str("the move leaves the king in check"),
+      IllegalMoveError::InvalidPromotion => f.write_str("invalid or missing promotion"),
+    }
+  }
+}
+
+impl Error for IllegalMoveError {}
+
+impl Board {
+  /// Get copy of board after applying a move, if it is legal in this
+  /// position. Unlike `apply_move` this never panics, so it's the one to use
+  /// for moves coming from users, files or other engines.
+  ///
+  /// Only the squares and the promotion piece of `mv` are looked at: whether
+  /// it is a capture, en passant or castling follows from the position, so
+  /// a move built as `MoveKind::Quiet` is accepted for a plain capture.
+  pub fn try_apply(&self, mv: Move) -> Result<Board, IllegalMoveError> {
+    let (from, to) = (mv.from(), mv.to());
+
+    let piece = self[from].ok_or(IllegalMoveError::NoPiece { square: from })?;
+    if piece.color != self.side_to_move {
+      return Err(IllegalMoveError::WrongColor { square: from });
+    }
+
+    let generated = moves_for_piece(self, from);
+    let mut reaching = generated.iter().filter(|m| m.to() == to).peekable();
+
+    if reaching.peek().is_none() {
+      return Err(self.diagnose(piece, mv));
+    }
+
+    let mv = *reaching
+      .find(|m| m.promotion() == mv.promotion())
+      .ok_or(IllegalMoveError::InvalidPromotion)?;
+
+    let mut board = *self;
+    if !leaves_king_safe(&mut board, mv) {
+      return Err(IllegalMoveError::LeavesKingInCheck);
+    }
+
+    board.make_move(mv);
+    Ok(board)
+  }
+
+  // why a piece of the side to move can't get from mv.from() to mv.to()
+  // at all, ignoring promotions and checks
+  fn diagnose(&self, piece: Piece, mv: Move) -> IllegalMoveError {
+    let (from, to) = (mv.from_sq(), mv.to_sq());
+    let (from_x, from_y) = mv.from();
+    let home_rank = if piece.color.is_white() { 7 } else { 0 };
+
+    if self.color_pieces(piece.color).contains(to) {
+      return IllegalMoveError::Blocked;
+    }
+
+    match piece.class {
+      PieceType::King
+        if from_y == home_rank && from_x == 4 && (to == from + 2 || to + 2 == from) =>
+      {
+        IllegalMoveError::CastlingNotAllowed
+      }
+      PieceType::Pawn => {
+        let direction = 8 * piece.color.direction();
+        let start_rank = if piece.color.is_white() { 6 } else { 1 };
+        let one = from as i32 + direction;
+        let two = from as i32 + 2 * direction;
+
+        if to as i32 == one || (to as i32 == two && from_y == start_rank) {
+          // pushes need every square up to the destination to be empty
+          IllegalMoveError::Blocked
+        } else {
+          // includes diagonal moves that don't capture anything
+          IllegalMoveError::InvalidMovement
+        }
+      }
+      class => {
+        if piece_attacks(class, from, Bitboard::EMPTY).contains(to) {
+          IllegalMoveError::Blocked
+        } else {
+          IllegalMoveError::InvalidMovement
+        }
+      }
+    }
+  }
+}
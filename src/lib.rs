@@ -0,0 +1,48 @@
+//! Headless chess rules engine: board representation, move generation,
+//! legality checks and search. Nothing in here depends on a graphics backend,
+//! so the GUI (and any other front end) is just a client of this crate.
+
+pub mod attacks;
+pub mod bitboard;
+pub mod board;
+pub mod book;
+pub mod epd;
+pub mod fen;
+pub mod frontend;
+pub mod legality;
+pub mod movegen;
+pub mod movelist;
+pub mod perft;
+pub mod pgn;
+pub mod piece;
+pub mod polyglot;
+pub mod san;
+pub mod search;
+pub mod see;
+pub mod square;
+pub mod status;
+pub mod tablebase;
+mod zobrist;
+
+pub use crate::bitboard::Bitboard;
+pub use crate::board::{Board, Move, MoveKind, ParseMoveError, Undo, MATE_SCORE};
+pub use crate::book::{Book, BookBuilder, BookError};
+pub use crate::epd::{Epd, EpdError};
+pub use crate::fen::{FenError, FenField, STARTING_FEN};
+pub use crate::legality::IllegalMoveError;
+pub use crate::movegen::{
+  can_castle, has_legal_move, inbounds, is_in_check, is_in_checkmate, is_in_stalemate,
+  is_move_legal, is_square_attacked, legal_moves, legal_moves_for_piece, moves_for_piece, to_coord,
+  to_offset, PROMO_OPTS,
+};
+pub use crate::movelist::MoveList;
+pub use crate::perft::{divide, perft};
+pub use crate::pgn::{
+  parse_pgn, parse_pgn_games, Annotation, Game, PgnError, PgnErrorKind, Variation,
+};
+pub use crate::piece::{Piece, PieceColor, PieceType};
+pub use crate::polyglot::polyglot_key;
+pub use crate::san::SanError;
+pub use crate::square::{ParseSquareError, Square};
+pub use crate::status::{GameResult, GameStatus};
+pub use crate::tablebase::{Outcome, Tablebase, TablebaseError};
@@ -1,7 +1,18 @@
-use std::mem::{self, swap};
-use std::ops::{Index, IndexMut};
-use std::time::Instant;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::time::{Instant, SystemTime};
 
+use chess::frontend::{
+  load_book, load_tablebase, next_random, print_outcome, random_seed, BOOK_PATH, TABLEBASE_DIR,
+};
+use chess::pgn::pgn_date;
+use chess::piece::*;
+use chess::search::minimax_with_tablebase;
+use chess::{
+  has_legal_move, legal_moves_for_piece, parse_pgn, Board, Game, GameStatus, Move, MoveList,
+  PROMO_OPTS,
+};
 use sfml::graphics::{
   Color, RectangleShape, RenderTarget, RenderWindow, Shape, Sprite, Texture, Transformable,
 };
@@ -10,6 +21,17 @@ use sfml::window::mouse::Button;
 use sfml::window::{ContextSettings, Event, Key, Style, VideoMode};
 use sfml::SfBox;
 
+// x-offsets for displaying
+const PAWN_XOFF: i32 = 10;
+const KNIGHT_XOFF: i32 = 5;
+const ROOK_XOFF: i32 = 6;
+const BISHOP_XOFF: i32 = 3;
+const QUEEN_XOFF: i32 = -2;
+const KING_XOFF: i32 = 2;
+
+// every game the engine plays a move in gets appended here
+const ARCHIVE_PATH: &str = "games.pgn";
+
 const SQUARE_SIZE: u32 = 100;
 const WINDOW_SIZE: u32 = 8 * SQUARE_SIZE;
 
@@ -21,16 +43,19 @@ fn color_mult(color: Color, multiplier: f64) -> Color {
   let f = |x: u8| ((x as f64 * multiplier) as u32).clamp(0, 255) as u8;
   Color::rgb(f(color.r), f(color.g), f(color.b))
 }
+// drawing lives here rather than in the library so that the rules engine
+// doesn't depend on SFML.
+trait DrawPiece {
+  fn draw(self, pos: (u32, u32), window: &mut RenderWindow, texture_map: &[SfBox<Texture>; 12]);
+  fn draw_precise(
+    self,
+    pos: (i32, i32),
+    window: &mut RenderWindow,
+    texture_map: &[SfBox<Texture>; 12],
+  );
+}
 
-mod piece;
-mod search;
-
-use crate::search::minimax;
-
-use crate::piece::PieceType::*;
-use crate::piece::*;
-
-impl Piece {
+impl DrawPiece for Piece {
   // draw the piece on the board
   fn draw(self, (x, y): (u32, u32), window: &mut RenderWindow, texture_map: &[SfBox<Texture>; 12]) {
     let idx = self.color as usize * 6 + self.class as usize;
@@ -83,224 +108,11 @@ impl Piece {
   }
 }
 
-// support Forsyth–Edwards Notation (FEN) notation
-
-type BoardState = Option<Piece>;
-
-#[derive(Copy, Clone, Debug)]
-pub struct Board {
-  board: [BoardState; 64],
-  // outer-
-  // [0] - white,
-  // [1] - black
-
-  // inner [bool;2] ->
-  //  [0] - left
-  //  [1] - right (in terms of x-axis, not necessarily from player's perspective)
-  castling_rights: [[bool; 2]; 2],
-
-  // en passant
-  // square that just moved
-  // after apply_move, this should be set to the square of the pawn
-  // that is currently there.
-
-  // square that CAN BE CAPTURED (is technically empty)
-
-  // should be set to none after every turn
-  en_passant_square: Option<(u32, u32)>,
-}
-
-// TODO represent this struct more compactly
-#[derive(Copy, Clone, Debug)]
-pub struct Move {
-  // (x, y)
-  from: (u32, u32),
-  // (x, y)
-  to: (u32, u32),
-
-  // pawn promotion
-  promotion: Option<Piece>,
-}
-
-impl Move {
-  #[inline]
-  fn coords(self) -> ((u32, u32), (u32, u32)) {
-    (self.from, self.to)
-  }
-
-  fn from_coords((x1, y1): (u32, u32), (x2, y2): (u32, u32)) -> Self {
-    Self {
-      from: (x1, y1),
-      to: (x2, y2),
-      promotion: None,
-    }
-  }
+trait DrawBoard {
+  fn draw(&self, window: &mut RenderWindow, texture_map: &[SfBox<Texture>; 12]);
 }
 
-impl Board {
-  // standard board setup
-  fn new() -> Self {
-    let mut board = [None; 64];
-
-    for j in 0..8 {
-      board[8 + j] = Some(BP);
-      board[8 * 6 + j] = Some(WP);
-    }
-
-    board[8 * 7] = Some(WR);
-    board[8 * 7 + 7] = Some(WR);
-    board[0] = Some(BR);
-    board[7] = Some(BR);
-    board[8 * 7 + 1] = Some(WN);
-    board[8 * 7 + 6] = Some(WN);
-    board[1] = Some(BN);
-    board[6] = Some(BN);
-    board[8 * 7 + 2] = Some(WB);
-    board[8 * 7 + 5] = Some(WB);
-    board[2] = Some(BB);
-    board[5] = Some(BB);
-    board[3] = Some(BQ);
-    board[8 * 7 + 3] = Some(WQ);
-    board[4] = Some(BK);
-    board[8 * 7 + 4] = Some(WK);
-
-    Self {
-      board,
-      castling_rights: [[true; 2]; 2],
-      en_passant_square: None,
-    }
-  }
-
-  /// Get copy of board after applying a move.
-  fn apply_move(&self, mv: Move) -> Board {
-    let ((x1, y1), (x2, y2)) = mv.coords();
-
-    let mut board = *self;
-
-    let en_passant_square = mem::take(&mut board.en_passant_square);
-
-    // handle castling
-    if board[(x1, y1)]
-      .map(|p| p.class == PieceType::King)
-      .unwrap_or(false)
-      && (x1 as i32 - x2 as i32).abs() == 2
-    {
-      debug_assert!(mv.promotion.is_none());
-
-      // direction
-      let is_rook_right = x2 > x1;
-      let color = board[(x1, y1)].unwrap().color;
-      let rank_idx = if color.is_white() { 7 } else { 0 };
-      let rook_idx = if is_rook_right { 7 } else { 0 };
-
-      assert!(y1 == y2 && y1 == rank_idx);
-      assert!(board.castling_rights[color as usize][is_rook_right as usize]);
-
-      // also cannot castle THROUGH check.
-
-      // assert rook is there
-      assert!(board[(rook_idx, y1)]
-        .map(|p| p.class == PieceType::Rook)
-        .unwrap_or(false));
-
-      // also ensure there are no pieces in between.
-
-      // move king
-      board
-        .board
-        .swap((8 * y1 + x1) as usize, (8 * y2 + x2) as usize);
-
-      let new_rook_x = if is_rook_right { x1 + 1 } else { x1 - 1 };
-      board
-        .board
-        .swap((8 * y1 + rook_idx) as usize, (8 * y1 + new_rook_x) as usize);
-
-      board.castling_rights[color as usize] = [false; 2];
-
-      board
-    } else if let Some(promo) = mv.promotion {
-      assert!(board[(x1, y1)]
-        .map(|p| p.class == PieceType::Pawn)
-        .unwrap_or(false));
-
-      // TODO add more checks here
-
-      // let pawn_rank =
-
-      board[(x1, y1)] = None;
-      board[(x2, y2)] = Some(promo);
-
-      board
-    } else {
-      // handle regular move
-
-      // TODO make sure after pawn promotion, you cannot promote to rook
-      // and castle with that rook
-      // although that shouldn't be possible if the original rooks don't move
-      // or get captured, in which case you lose castling rights anyway.
-
-      debug_assert!(mv.promotion.is_none());
-
-      // TODO: && castling rights exist
-      // just short-circuit optimization, won't change results
-      if let Some(piece) = board[(x1, y1)] {
-        let color = piece.color;
-        // TODO optimize this
-        match piece.class {
-          // handle castling
-          PieceType::King => {
-            board.castling_rights[piece.color as usize] = [false; 2];
-          }
-          PieceType::Rook => {
-            let y_idx = if piece.color.is_black() { 0 } else { 7 };
-            if (x1, y1) == (0, y_idx) {
-              board.castling_rights[piece.color as usize][0] = false;
-            } else if (x1, y1) == (7, y_idx) {
-              board.castling_rights[piece.color as usize][1] = false;
-            }
-          }
-          // handle 2 pawn move (en passant)
-          PieceType::Pawn if (y1 as i32 - y2 as i32).abs() == 2 => {
-            debug_assert!([1, 6].contains(&y1));
-            debug_assert!(x1 == x2);
-
-            board.en_passant_square = Some((
-              x2,
-              (y2 as i32 - piece.color.direction()).try_into().unwrap(),
-            ));
-          }
-          _ => {}
-        }
-
-        match (en_passant_square, piece.class) {
-          (Some((epx, epy)), PieceType::Pawn) if (epx, epy) == (x2, y2) => {
-            debug_assert!(board[(epx, epy)].is_none());
-
-            let pawn_capture =
-              mem::take(&mut board[(epx, (epy as i32 + (!color).direction()).try_into().unwrap())]);
-            debug_assert_eq!(
-              pawn_capture,
-              Some(Piece {
-                class: PieceType::Pawn,
-                color: !color
-              })
-            );
-            board[(x2, y2)] = board[(x1, y1)];
-            board[(x1, y1)] = None;
-            board
-          }
-          _ => {
-            board[(x2, y2)] = board[(x1, y1)];
-            board[(x1, y1)] = None;
-            board
-          }
-        }
-      } else {
-        unreachable!("starting square should not be empty in apply_move()")
-      }
-    }
-  }
-
+impl DrawBoard for Board {
   fn draw(&self, window: &mut RenderWindow, texture_map: &[SfBox<Texture>; 12]) {
     for i in 0..8u32 {
       for j in 0..8u32 {
@@ -310,733 +122,16 @@ impl Board {
       }
     }
   }
-
-  // very simple evaluation, just sum up piece values
-  // positive = advantage for white, negative = advantage for black
-  fn eval(&self, to_move: PieceColor) -> i32 {
-    if is_in_checkmate(self, to_move) {
-      return if to_move.is_white() {
-        -1_000_000
-      } else {
-        1_000_000
-      };
-    }
-
-    self
-      .board
-      .iter()
-      .enumerate()
-      .map(|(idx, square)| match square {
-        Some(p) => {
-          let mult = if p.color.is_white() { 1 } else { -1 };
-
-          let (magnitude, table) = match p.class {
-            Pawn => (100, &PAWN_TABLE),
-            Knight => (320, &KNIGHT_TABLE),
-            Bishop => (330, &BISHOP_TABLE),
-            Rook => (500, &ROOK_TABLE),
-            Queen => (900, &QUEEN_TABLE),
-            King => (20_000, &KING_TABLE),
-          };
-
-          let (x, y) = to_coord(idx as u32);
-          let y = if p.color.is_white() { y } else { 7 - y };
-
-          mult * (magnitude + table[(y * 8 + x) as usize])
-        }
-        None => 0,
-      })
-      .sum()
-  }
-
-  fn moves_for_player(&self, color: PieceColor) -> Vec<Move> {
-    let mut moves = vec![];
-
-    for x in 0..8u32 {
-      for y in 0..8u32 {
-        if let Some(piece) = self[(x, y)] {
-          if piece.color == color {
-            let p_mvs = moves_for_piece(self, (x, y));
-            moves.extend_from_slice(&p_mvs);
-          }
-        }
-      }
-    }
-
-    // TODO deduplicate this code
-
-    // retain moves that don't put us in check
-    // closure returns false for illegal moves, true for legal
-    moves.retain(|&mv| {
-      is_move_legal(self, mv) && {
-        let board_after_move = self.apply_move(mv);
-        !is_in_check(&board_after_move, color)
-      }
-    });
-
-    moves
-  }
-}
-
-impl Index<usize> for Board {
-  type Output = Option<Piece>;
-  fn index(&self, index: usize) -> &Self::Output {
-    &self.board[index]
-  }
-}
-
-impl Index<u32> for Board {
-  type Output = Option<Piece>;
-  fn index(&self, index: u32) -> &Self::Output {
-    &self.board[index as usize]
-  }
-}
-
-impl<T: Into<u32>> Index<(T, T)> for Board {
-  type Output = Option<Piece>;
-  fn index(&self, (x, y): (T, T)) -> &Self::Output {
-    &self.board[8 * y.into() as usize + x.into() as usize]
-  }
-}
-
-impl IndexMut<usize> for Board {
-  fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-    &mut self.board[index]
-  }
-}
-
-impl IndexMut<u32> for Board {
-  fn index_mut(&mut self, index: u32) -> &mut Self::Output {
-    &mut self.board[index as usize]
-  }
-}
-
-impl<T: Into<u32>> IndexMut<(T, T)> for Board {
-  fn index_mut(&mut self, (x, y): (T, T)) -> &mut Self::Output {
-    &mut self.board[8 * y.into() as usize + x.into() as usize]
-  }
-}
-
-#[inline(always)]
-fn sort2<T: Copy + Ord>(x: T, y: T) -> (T, T) {
-  if x < y {
-    (x, y)
-  } else {
-    (y, x)
-  }
 }
 
-fn is_bishop_move_legal(board: &Board, (x1, y1): (u32, u32), (x2, y2): (u32, u32)) -> bool {
-  if (x1 as i32 - x2 as i32).abs() == (y1 as i32 - y2 as i32).abs() {
-    let n_rows = {
-      let (yy1, yy2) = sort2(y1 as i32, y2 as i32);
-      yy2 - yy1 - 1
-    };
-
-    let (mut x1, mut x2, mut y1, mut y2) = (x1, x2, y1, y2);
-
-    if y1 > y2 {
-      // swap (x1, y1) and (x2, y2)
-      swap(&mut x1, &mut x2);
-      swap(&mut y1, &mut y2);
-    }
-
-    let stride = if x1 < x2 { 9 } else { 7 };
-
-    let mut idx = 8 * y1 + x1;
-    for _ in 0..n_rows {
-      idx += stride;
-      if board[idx].is_some() {
-        return false;
-      }
-    }
-
-    true
-  } else {
-    false
-  }
-}
-
-// doesn't check for self-capture as that is checked universally for all moves.
-fn is_rook_move_legal(board: &Board, (x1, y1): (u32, u32), (x2, y2): (u32, u32)) -> bool {
-  let x_match = x1 == x2;
-  if x_match ^ (y1 == y2) {
-    let (x1, x2) = sort2(x1, x2);
-    let (y1, y2) = sort2(y1, y2);
-
-    if x_match {
-      // [(x1, y1 + 1), (x1, y2 - 1)]
-      for y in y1 + 1..=y2 - 1 {
-        if board[(x1, y)].is_some() {
-          return false;
-        }
-      }
-    } else {
-      // [(x1 + 1, y1), (x2 - 1, y1)]
-      for x in x1 + 1..=x2 - 1 {
-        if board[(x, y1)].is_some() {
-          return false;
-        }
-      }
-    }
-
-    true
-  } else {
-    false
-  }
-}
-
-#[inline(always)]
-pub const fn to_offset(x: i32, y: i32) -> i32 {
-  8 * y + x
-}
-
-#[inline(always)]
-pub const fn to_coord(idx: u32) -> (u32, u32) {
-  (idx % 8, idx / 8)
-}
-
-// pub fn get_offsets()
-
-// maybe keep track of what moves were played so that it is easy
-// to revert them, to avoid making copies of the board to check
-// for check.
-
-pub fn is_in_checkmate(board: &Board, player: PieceColor) -> bool {
-  // loop through possible all moves, see if any of them do not put you in check
-
-  for i in 0..=63 {
-    match board[i as usize] {
-      Some(p) if p.color == player => {
-        let (x, y) = to_coord(i);
-        let moves = moves_for_piece(board, (x, y));
-        for mv in moves {
-          if !is_in_check(&board.apply_move(mv), player) {
-            return false;
-          }
-        }
-      }
-      _ => {}
-    }
-  }
-
-  true
-}
-
-pub fn is_in_check(board: &Board, player: PieceColor) -> bool {
-  // loop through all opponent pieces, except for king (debug assert maybe?).
-
-  // for each of the opponent's pieces, check if any of the squares they
-  // attack cover our king.
-
-  // find index of player's king
-  let (kx, ky) = to_coord(
-    board
-      .board
-      .iter()
-      .position(|&p| {
-        p == Some(Piece {
-          class: PieceType::King,
-          color: player,
-        })
-      })
-      .expect("king should always exist on board") as u32,
-  );
-
-  // could also maybe just keep track of the board state some other way
-  // to avoid looping through the board?
-  // but I think this is fine for now...
-
-  // loop through opponent's pieces
-  for i in 0..=63 {
-    match board[i as usize] {
-      // Some(p) if p.color != player && p.class != PieceType::King => {
-      Some(p) if p.color != player => {
-        // check if any of their moves covers our king
-        let squares = moves_for_piece(board, to_coord(i));
-
-        for mv in squares {
-          if mv.to == (kx, ky) {
-            return true;
-          }
-        }
-      }
-      _ => {}
-    }
-  }
-
-  false
-}
-
-#[inline(always)]
-pub fn inbounds(x: i32, y: i32) -> bool {
-  (0..=7).contains(&x) && (0..=7).contains(&y)
-}
-
-// mv!(..., ..., (x, y))
-macro_rules! mv {
-  ($x:expr, $y:expr, $from:expr) => {
-    Move::from_coords($from, ($x, $y))
-  };
-}
-
-fn moves_for_sliding_piece(
-  board: &Board,
-  (x, y): (u32, u32),
-  directions: &[(i32, i32)],
-) -> Vec<Move> {
-  debug_assert!(board[(x, y)]
-    .map(|p| matches!(
-      p.class,
-      PieceType::Rook | PieceType::Bishop | PieceType::Queen
-    ))
-    .unwrap_or(false));
-
-  if let Some(p) = board[(x, y)] {
-    let mut moves = vec![];
-
-    for (xd, yd) in directions {
-      let mut xt = x as i32 + xd;
-      let mut yt = y as i32 + yd;
-      while inbounds(xt, yt) {
-        if let Some(p2) = board[(xt as u32, yt as u32)] {
-          if p2.color != p.color {
-            moves.push(mv!(xt as u32, yt as u32, (x, y)));
-          }
-          break;
-        } else {
-          moves.push(mv!(xt as u32, yt as u32, (x, y)));
-        }
-
-        xt += xd;
-        yt += yd;
-      }
-    }
-
-    moves
-  } else {
-    unreachable!()
-  }
-}
-
-static ROOK_DIRECTIONS: [(i32, i32); 4] = [(-1, 0), (1, 0), (0, 1), (0, -1)];
-static BISHOP_DIRECTIONS: [(i32, i32); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
-static QUEEN_DIRECTIONS: [(i32, i32); 8] = [
-  (-1, 0),
-  (1, 0),
-  (0, 1),
-  (0, -1),
-  (-1, -1),
-  (-1, 1),
-  (1, -1),
-  (1, 1),
-];
-
-// return vector of final coordinates for piece
-// TODO (important) update this to handle pawn promotion as a possible move
-// otherwise checking for checkmate will be wrong in some cases.
-// fn moves_for_piece(board: &Board, (x, y): (u32, u32)) -> Vec<(u32, u32)> {
-fn moves_for_piece(board: &Board, (x, y): (u32, u32)) -> Vec<Move> {
-  // we generate offsets, then maybe also check further legality of the move?
-  // i.e. we do not put our own king in check by making this move
-  if let Some(p) = board[(x, y)] {
-    match p.class {
-      PieceType::Knight => {
-        let mut moves = vec![];
-        let possible_moves = [
-          (-2, 1),
-          (-2, -1),
-          (2, 1),
-          (2, -1),
-          (1, -2),
-          (-1, -2),
-          (1, 2),
-          (-1, 2),
-        ];
-
-        // filter out moves that are off the board and self-capture
-        for (xoff, yoff) in possible_moves {
-          let xn = x as i32 + xoff;
-          let yn = y as i32 + yoff;
-          if inbounds(xn, yn)
-            && board[(xn as u32, yn as u32)]
-              .map(|p2| p2.color != p.color)
-              .unwrap_or(true)
-          {
-            moves.push(mv!(xn as u32, yn as u32, (x, y)));
-          }
-        }
-
-        moves
-      }
-      PieceType::Rook => moves_for_sliding_piece(board, (x, y), &ROOK_DIRECTIONS),
-      PieceType::Bishop => moves_for_sliding_piece(board, (x, y), &BISHOP_DIRECTIONS),
-      PieceType::Queen => moves_for_sliding_piece(board, (x, y), &QUEEN_DIRECTIONS),
-      PieceType::King => {
-        let mut moves = vec![];
-
-        let move_offsets = [
-          (-1, -1),
-          (0, -1),
-          (1, -1),
-          (-1, 0),
-          (1, 0),
-          (-1, 1),
-          (0, 1),
-          (1, 1),
-        ];
-
-        for (xoff, yoff) in move_offsets {
-          let (xd, yd) = (x as i32 + xoff, y as i32 + yoff);
-          if inbounds(xd, yd)
-            && board[(xd as u32, yd as u32)]
-              .map(|p2| p2.color != p.color)
-              .unwrap_or(true)
-          {
-            moves.push(mv!(xd as u32, yd as u32, (x, y)));
-          }
-        }
-
-        let rank_yidx = if p.color.is_white() { 7u32 } else { 0 };
-        // if board.castling_rights[p.color as usize] && y == rank_yidx {
-        if y == rank_yidx {
-          // add castling moves
-          // TODO update castling rights for each rook
-          // TODO maybe also check that king is on original square,
-          // but maybe this can be done with accurate castling rights.
-
-          for rook_idx in [0u32, 7] {
-            if let (
-              true,
-              Some(Piece {
-                class: PieceType::Rook,
-                color,
-              }),
-            ) = (
-              board.castling_rights[p.color as usize][(rook_idx != 0) as usize],
-              board[(rook_idx, rank_yidx)],
-            ) {
-              if color == p.color {
-                // maybe add
-
-                let mut idxs: [u32; 2] = [x, rook_idx];
-                idxs.sort();
-
-                let is_rook_right = rook_idx != 0;
-                if !do_pieces_exist_x1x2(board, rank_yidx, (idxs[0] + 1, idxs[1] - 1)) && {
-                  let mut is_legal = true;
-
-                  let xoff = if is_rook_right { 1 } else { -1 };
-                  // TODO check godbolt diff
-                  // let xoff = is_rook_right as i32 * 2 - 1;
-
-                  for (idx, square) in board.board.iter().enumerate() {
-                    if let Some(p2) = square {
-                      if p2.color != p.color && p2.class != PieceType::King {
-                        if moves_for_piece(board, to_coord(idx as u32))
-                          .iter()
-                          .any(|mv| [(x, y), ((x as i32 + xoff) as u32, y)].contains(&mv.to))
-                        {
-                          is_legal = false;
-                          break;
-                        }
-                      }
-                    }
-                  }
-
-                  is_legal
-                } {
-                  // if no pieces exist in between and we have castling rights, we can
-                  // add this as a move
-                  let king_xoff: i32 = if is_rook_right { 2 } else { -2 };
-
-                  moves.push(mv!(
-                    (x as i32 + king_xoff).try_into().unwrap(),
-                    rank_yidx,
-                    (x, y)
-                  ));
-                }
-              }
-            }
-          }
-        }
-
-        moves
-      }
-      PieceType::Pawn => {
-        let mut moves = vec![];
-
-        let direction = if p.color.is_white() { -1 } else { 1 };
-
-        let last_rank = if p.color.is_white() { 0 } else { 7 };
-
-        // basic move, push forward 1
-        let (bx, by) = (x as i32, y as i32 + direction);
-        if inbounds(bx, by) && board[(bx as u32, by as u32)].is_none() {
-          if by == last_rank {
-            for pt in PROMO_OPTS {
-              moves.push(Move {
-                from: (x, y),
-                to: (bx as u32, by as u32),
-                promotion: Some(Piece {
-                  class: pt,
-                  color: p.color,
-                }),
-              });
-            }
-          } else {
-            moves.push(mv!(bx as u32, by as u32, (x, y)));
-          }
-        }
-
-        // push 2 if on rank 2
-        let push2_rank = if p.color.is_white() { 6 } else { 1 };
-        if y == push2_rank {
-          // sanity check; this should always be in bounds because of the
-          // rank the pawn is on.
-          debug_assert!(inbounds(x as i32, y as i32 + 2 * direction));
-
-          let (px, py) = (x, (y as i32 + 2 * direction) as u32);
-          if board[(px, py)].is_none() && board[(px, (y as i32 + direction) as u32)].is_none() {
-            moves.push(mv!(px, py, (x, y)));
-          }
-        }
-
-        // sideways attacking moves
-        for xoff in [-1, 1] {
-          let (ax, ay) = (x as i32 + xoff, y as i32 + direction);
-          if inbounds(ax, ay)
-            && match board[(ax as u32, ay as u32)] {
-              Some(p2) => p2.color != p.color,
-              None => Some((ax as u32, ay as u32)) == board.en_passant_square,
-            }
-          {
-            // TODO: dedup code from previous?
-            if ay == last_rank {
-              for pt in PROMO_OPTS {
-                moves.push(Move {
-                  from: (x, y),
-                  to: (ax as u32, ay as u32),
-                  promotion: Some(Piece {
-                    class: pt,
-                    color: p.color,
-                  }),
-                });
-              }
-            } else {
-              moves.push(mv!(ax as u32, ay as u32, (x, y)));
-            }
-          }
-        }
-
-        moves
-      }
-    }
-  } else {
-    unreachable!("function should not be called on empty square")
-  }
-}
-
-// check if any pieces exist on a certain rank between x1 and x2 (inclusive)
-fn do_pieces_exist_x1x2(board: &Board, rank_idx: u32, (x1, x2): (u32, u32)) -> bool {
-  debug_assert!(x1 <= x2);
-
-  for x in x1..=x2 {
-    if board[(x, rank_idx)].is_some() {
-      return true;
-    }
-  }
-
-  false
-}
-
-fn is_move_legal(board: &Board, mv: Move) -> bool {
-  let ((x1, y1), (x2, y2)) = mv.coords();
-
-  // TODO do not allow moves that put your king in check
-  if (x1, y1) == (x2, y2) {
-    return false;
-  }
-
-  // TODO maybe check other obviously ridiculous scenarios here as well,
-  // possibly as a debug assert?
-
-  // ensure (x1, y1) exists and that if it is not a pawn, it doesn't
-  // have a promotion.
-  assert!(board[(x1, y1)]
-    .map(|p| if !p.is_pawn() {
-      mv.promotion.is_none()
-    } else {
-      true
-    })
-    .unwrap_or(false));
-
-  if let Some(piece) = board[(x1, y1)] {
-    match piece.class {
-      PieceType::Pawn => {
-        // en pessant as well...
-
-        let y_dist = || y2 as i32 - y1 as i32;
-
-        // rank2 is the rank where 2 moves as a pawn is allowed.
-        let (rank2, file_range, direction) = match piece.color {
-          PieceColor::White => (6, (-2..=-1), -1),
-          PieceColor::Black => (1, (1..=2), 1),
-        };
-
-        // TODO clean up this code
-        (if let Some(captured_piece) = board[(x2, y2)] {
-          // regular capture
-          captured_piece.color != piece.color
-            && (x1 as i32 - x2 as i32).abs() == 1
-            && y_dist() == direction
-        } else if x1 != x2 {
-          // en passant
-
-          // due to order of if statement, it is guaranteed that board[(x2, y2)] is None
-          // in this branch
-
-          board.en_passant_square == Some((x2, y2))
-            && (x1 as i32 - x2 as i32).abs() == 1
-            && y_dist() == direction
-        } else if y1 == rank2 {
-          // move from starting square (can move 1 or 2 squares forward)
-          x1 == x2
-            && file_range.contains(&y_dist())
-            && (1..=y_dist())
-              .all(|r_off| board[(x1, (y1 as i32 + r_off * direction) as u32)].is_none())
-        } else {
-          // basic pawn move, 1 forward
-          (x2, y2 as i32) == (x1, y1 as i32 + direction)
-        }) && ({
-          let last_rank = if piece.color.is_white() { 0 } else { 7 };
-
-          if y2 == last_rank {
-            // promotion exists and is valid
-            mv.promotion
-              // TODO: optimize to just simple range check instead of loop
-              .map(|pr| PROMO_OPTS.contains(&pr.class) && pr.color == piece.color)
-              .unwrap_or(false)
-          } else {
-            true
-          }
-        })
-      }
-      PieceType::Knight => {
-        let xdist = (x1 as i32 - x2 as i32).abs() - 1;
-        let ydist = (y1 as i32 - y2 as i32).abs() - 1;
-
-        // ensure all bits except for lsb are 0
-        // only need to check one of xdist or ydist, since
-        // if all bits except lsb are 0, that means
-        // it REQUIRES all top bits of ydist to also be 0,
-        // otherwise xdist ^ ydist != 1.
-
-        xdist ^ ydist == 1 && xdist & !1 == 0
-      }
-      PieceType::Bishop => is_bishop_move_legal(board, (x1, y1), (x2, y2)),
-      PieceType::Rook => is_rook_move_legal(board, (x1, y1), (x2, y2)),
-      PieceType::Queen => {
-        let xdist = (x1 as i32 - x2 as i32).abs();
-        let ydist = (y1 as i32 - y2 as i32).abs();
-
-        if xdist == ydist {
-          is_bishop_move_legal(board, (x1, y1), (x2, y2))
-        } else if (xdist == 0) ^ (ydist == 0) {
-          is_rook_move_legal(board, (x1, y1), (x2, y2))
-        } else {
-          false
-        }
-      }
-      PieceType::King => {
-        let xdist = (x1 as i32 - x2 as i32).abs();
-
-        match xdist {
-          0 | 1 => {
-            let ydist = (y1 as i32 - y2 as i32).abs();
-            ydist <= 1
-          }
-          2 => {
-            let rank_idx = if piece.color.is_white() { 7 } else { 0 };
-            let file_idx = 4;
-            if y1 != y2 || y1 != rank_idx {
-              return false;
-            }
-            if x1 != file_idx {
-              return false;
-            }
-
-            let is_rook_right = x2 > x1;
-
-            // ensure castling rights exist
-            if !board.castling_rights[piece.color as usize][is_rook_right as usize] {
-              return false;
-            }
-
-            // in terms of x index, not necessarily from player's perspective
-            let rook_idx = if is_rook_right { 7 } else { 0 };
-
-            if let Some(Piece {
-              class: PieceType::Rook,
-              color,
-            }) = board[(rook_idx, rank_idx)]
-            {
-              if color != piece.color {
-                return false;
-              }
-            } else {
-              return false;
-            }
-
-            let mut xidx = [x1, rook_idx];
-            xidx.sort();
-
-            // TODO also check castle THROUGH check here
-            if do_pieces_exist_x1x2(board, y1, (xidx[0] + 1, xidx[1] - 1)) {
-              return false;
-            }
-
-            // Can't castle if under check
-            if is_in_check(board, piece.color) {
-              return false;
-            }
-
-            // Can't castle through a check either
-            let xoff = if is_rook_right { 1 } else { -1 };
-
-            debug_assert!(inbounds(x1 as i32, y1 as i32));
-
-            let mut board_copy = *board;
-            board_copy.board.swap(
-              (y1 * 8 + x1) as usize,
-              ((y1 * 8 + x1) as i32 + xoff) as usize,
-            );
-
-            if is_in_check(&board_copy, piece.color) {
-              return false;
-            }
-
-            true
-          }
-          _ => false,
-        }
-      }
-    }
-  } else {
-    // shouldn't happen
-    debug_assert!(false);
-
-    false
-  }
-}
-
-static PROMO_OPTS: [PieceType; 4] = [Knight, Bishop, Rook, Queen];
+// (selected square, mouse position, legal moves for the selected piece)
+type Selection = Option<((u32, u32), (i32, i32), MoveList)>;
 
 fn draw_board(
   board: Board,
   window: &mut RenderWindow,
   texture_map: &[SfBox<Texture>; 12],
-  selection: &Option<((u32, u32), (i32, i32), Vec<Move>)>,
+  selection: &Selection,
   draw_selection: bool,
 ) {
   window.clear(LIGHT);
@@ -1062,7 +157,7 @@ fn draw_board(
     (Some(((sx, sy), (xd, yd), moves)), true) => {
       // no if-let chain on stable :(
       for mv in moves {
-        let (xd, yd) = mv.to;
+        let (xd, yd) = mv.to();
 
         let is_dark = (xd ^ yd) & 1 != 0;
         // TODO maybe blend with another color slightly or something?
@@ -1080,7 +175,8 @@ fn draw_board(
 
       let mut board_copy = board;
 
-      if let Some(selected_piece) = mem::take(&mut board_copy[(*sx, *sy)]) {
+      if let Some(selected_piece) = board_copy[(*sx, *sy)] {
+        board_copy.set_piece((*sx, *sy), None);
         board_copy.draw(window, texture_map);
         selected_piece.draw_precise(
           (
@@ -1111,6 +207,79 @@ fn print_checkmate(winner: PieceColor) {
   println!("============================================");
 }
 
+fn print_status(game: &Game) {
+  match game.status() {
+    GameStatus::Ongoing => {}
+    GameStatus::Checkmate { winner } => print_checkmate(winner),
+    status => {
+      println!("============================================");
+      println!("===              !!! DRAW !!!            ===");
+      println!("============================================");
+      println!("{}", status);
+    }
+  }
+}
+
+fn write_pgn(game: &Game) -> io::Result<()> {
+  let mut file = OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(ARCHIVE_PATH)?;
+  file.write_all(game.to_pgn().as_bytes())
+}
+
+// Append the game to the archive if the engine played in it and it hasn't
+// been saved yet. `engine_sides` says which colors the engine has moved for,
+// indexed by `PieceColor`.
+fn archive_game(game: &mut Game, engine_sides: [bool; 2], archived: &mut bool) {
+  if *archived || !engine_sides.contains(&true) {
+    return;
+  }
+
+  // the GUI stops at a draw that could be claimed, so record it as claimed
+  let status = game.status();
+  if status.is_claimable() && game.result().is_none() {
+    game.set_declared_result(status.result());
+  }
+
+  for (tag, engine) in ["White", "Black"].into_iter().zip(engine_sides) {
+    if engine {
+      game.set_tag(tag, "chess-gui");
+    } else if game.tag(tag).is_none() {
+      game.set_tag(tag, "Human");
+    }
+  }
+
+  match write_pgn(game) {
+    Ok(()) => {
+      *archived = true;
+      println!("[Info] Game saved to {}", ARCHIVE_PATH);
+    }
+    Err(err) => println!("[Error] Could not save game to {}: {}", ARCHIVE_PATH, err),
+  }
+}
+
+// game `number` (counting from 1) of the PGN file at `path`
+fn load_game(path: &str, number: usize) -> Result<Game, String> {
+  let text = fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+  let mut games = parse_pgn(&text)
+    .map_err(|err| format!("{}:{}:{}: {}", path, err.line, err.column, err.kind))?;
+
+  let count = games.len();
+  if number == 0 || number > count {
+    return Err(format!("{} has {} games, no game {}", path, count, number));
+  }
+  Ok(games.swap_remove(number - 1))
+}
+
+fn new_game() -> Game {
+  let mut game = Game::new();
+  game.set_tag("Event", "Casual game");
+  game.set_tag("Site", "chess-gui");
+  game.set_tag("Date", &pgn_date(SystemTime::now()));
+  game
+}
+
 fn main() {
   let max_aa = sfml::graphics::RenderTexture::maximum_antialiasing_level();
 
@@ -1152,23 +321,44 @@ fn main() {
     Texture::from_file("./resources/b_king.png").unwrap(),
   ];
 
-  let mut board = Board::new();
-  let mut selection: Option<((u32, u32), (i32, i32), Vec<Move>)> = None;
+  // usage: chess [FILE.pgn [GAME]] to continue from the end of a saved game
+  let args: Vec<String> = env::args().collect();
+  let mut game = match args.get(1) {
+    Some(path) => {
+      let number = args.get(2).and_then(|n| n.parse().ok()).unwrap_or(1);
+      load_game(path, number).unwrap_or_else(|err| {
+        println!("[Error] {}", err);
+        new_game()
+      })
+    }
+    None => new_game(),
+  };
+
+  let mut board = *game.board();
+  let mut selection: Selection = None;
 
-  let mut board_states = vec![board];
+  let mut engine_sides = [false; 2];
+  let mut archived = false;
 
-  let mut to_move = PieceColor::White;
+  let book = load_book(BOOK_PATH);
+  let tablebase = load_tablebase(TABLEBASE_DIR);
+  let mut random_state = random_seed();
 
   let mut depth_white = 1;
   let mut depth_black = 1;
 
   loop {
     while let Some(event) = window.poll_event() {
+      let to_move = board.side_to_move();
+
       match event {
         Event::Closed
         | Event::KeyPressed {
           code: Key::Escape, ..
-        } => return,
+        } => {
+          archive_game(&mut game, engine_sides, &mut archived);
+          return;
+        }
 
         // Event::KeyPressed { code: Key::, .. }=>{}
 
@@ -1176,10 +366,12 @@ fn main() {
         Event::KeyPressed {
           code: Key::Space, ..
         } => {
-          println!("Current eval: {}", board.eval(to_move));
+          println!("Current eval: {}", board.eval());
 
-          // shit does not work properly in regards to check,
-          // search does not seem to consider legal moves.
+          if !has_legal_move(&board) {
+            println!("No legal moves in this position ({:?} to move)", to_move);
+            continue;
+          }
 
           let depth = if to_move.is_white() {
             depth_white
@@ -1187,43 +379,45 @@ fn main() {
             depth_black
           };
 
-          let start_time = Instant::now();
-
-          let search_result = minimax(board, depth, to_move, i32::MIN, i32::MAX);
-          println!(
-            "minimax: (depth {} ply, {:?} time): {:?}\n",
-            depth,
-            start_time.elapsed(),
-            search_result
-          );
-
-          if let Some(mv) = search_result.0 {
-            board = board.apply_move(mv);
-
-            board_states.push(board);
-
-            to_move = !to_move;
+          let search_result = match book.pick(&board, next_random(&mut random_state)) {
+            Some(mv) => {
+              println!("[Info] Book move");
+              (Some(mv), board.eval())
+            }
+            None => {
+              let start_time = Instant::now();
 
-            // TODO: deduplicate code
+              if let Some(outcome) = tablebase.probe(&board) {
+                print_outcome(&board, outcome);
+              }
 
-            if is_in_checkmate(&board, to_move) {
-              print_checkmate(!to_move);
-            }
-          } else {
-            // TODO fix behavior
-
-            let moves = board.moves_for_player(to_move);
-            if moves.is_empty() {
-              println!("No legal moves in this position ({:?} to move)", to_move);
-            } else {
-              println!("(Forced mate -- minimax returned no results)");
-              println!("Picking arbitrary move");
-              let mv = moves[0];
-              board = board.apply_move(mv);
-              board_states.push(board);
-
-              to_move = !to_move;
+              let search_result = minimax_with_tablebase(
+                board,
+                &game.positions()[..game.positions().len() - 1],
+                depth,
+                &tablebase,
+              );
+              println!(
+                "minimax: (depth {} ply, {:?} time): {:?}\n",
+                depth,
+                start_time.elapsed(),
+                search_result
+              );
+              search_result
             }
+          };
+
+          let mv = search_result
+            .0
+            .expect("the search returns a move when there are legal moves");
+          println!("{}", mv.to_san(&board));
+          game.push(mv);
+          board = *game.board();
+          engine_sides[to_move as usize] = true;
+
+          print_status(&game);
+          if game.status().is_over() {
+            archive_game(&mut game, engine_sides, &mut archived);
           }
         }
 
@@ -1240,13 +434,10 @@ fn main() {
                 println!("[Info] Search depth (White) set to {} ply", num)
               }
             }
-          } else if code == Key::Left {
-            if board_states.len() > 1 {
-              if let (_, Some(&previous_board)) = (board_states.pop(), board_states.last()) {
-                to_move = !to_move;
-                board = previous_board;
-              }
-            }
+          } else if code == Key::Left && game.pop().is_some() {
+            board = *game.board();
+            // playing on makes it a different game
+            archived = false;
           }
         }
 
@@ -1267,16 +458,7 @@ fn main() {
           if let Some(piece) = board[(xn, yn)] {
             // only allow selecting color to move
             if piece.color == to_move {
-              // ok something is not working...
-              let mut moves = moves_for_piece(&board, (xn, yn));
-
-              // retain moves that don't put us in check
-              // closure returns false for illegal moves, true for legal
-              moves.retain(|&mv| {
-                let board_after_move = board.apply_move(mv);
-                !is_in_check(&board_after_move, to_move)
-              });
-
+              let moves = legal_moves_for_piece(&board, (xn, yn));
               selection = Some(((xn, yn), (x, y), moves));
             }
           }
@@ -1289,7 +471,7 @@ fn main() {
         } => {
           let (xn, yn) = (x as u32 / SQUARE_SIZE, y as u32 / SQUARE_SIZE);
 
-          if let Some(((ox, oy), _, _)) = selection {
+          if let Some(((ox, oy), _, ref moves)) = selection {
             // TODO if-let chain or something, this is too much nesting
             if let Some(old_piece) = board[(ox, oy)] {
               let old_color = old_piece.color;
@@ -1301,46 +483,27 @@ fn main() {
               };
 
               if (ox, oy) != (xn, yn) && new_piece_isnt_same_color() {
-                // move needs to be legal AND cannot put us in check after we do it
-
-                // check for pawn promotion, if so, don't apply move, change state to
-                // menu selection and then apply move after selecting pawn move.
-
-                let pawn_last_rank = if old_color.is_white() { 0 } else { 7 };
-
-                let is_pawn_promotion = || {
-                  old_piece.is_pawn() && yn == pawn_last_rank && (oy as i32 - yn as i32).abs() == 1
-                };
-
-                let mut promotion: Option<Piece> = None;
+                // the selection only holds legal moves, so the move has to be one of them
 
-                // legal moves are ones that do not put you in check
-                // whether you are in check doesn't depend on what types of pieces you
-                // have, it just depends on whether an enemy piece attacks yours
+                // move generation emits one move per promotion piece, so if more than
+                // one legal move lands on this square it is a pawn promotion: don't
+                // apply the move yet, show the menu and pick the move matching the
+                // selected piece.
+                let candidates: Vec<Move> = moves
+                  .iter()
+                  .copied()
+                  .filter(|mv| mv.to() == (xn, yn))
+                  .collect();
 
-                // therefore we can just use any piece type for promotion for
-                // purposes of legality check
+                let mut promotion: Option<PieceType> = None;
 
-                // TODO add early exit if move isn't legal (but is_pawn_promotion()==true)
-
-                if is_pawn_promotion()
-                  && is_move_legal(
-                    &board,
-                    Move {
-                      from: (ox, oy),
-                      to: (xn, yn),
-                      promotion: Some(Piece {
-                        class: PieceType::Knight,
-                        color: old_piece.color,
-                      }),
-                    },
-                  )
-                {
+                if candidates.len() > 1 {
                   {
                     let mut board_copy = board;
                     // move pawn for display purposes
-                    let pawn = mem::take(&mut board_copy[(ox, oy)]);
-                    board_copy[(xn, yn)] = pawn;
+                    let pawn = board_copy[(ox, oy)];
+                    board_copy.set_piece((ox, oy), None);
+                    board_copy.set_piece((xn, yn), pawn);
 
                     draw_board(board_copy, &mut window, &texture_map, &selection, false);
                   }
@@ -1374,7 +537,10 @@ fn main() {
                         Event::Closed
                         | Event::KeyPressed {
                           code: Key::Escape, ..
-                        } => return,
+                        } => {
+                          archive_game(&mut game, engine_sides, &mut archived);
+                          return;
+                        }
 
                         Event::MouseButtonPressed {
                           button: Button::Left,
@@ -1400,10 +566,7 @@ fn main() {
 
                             let selected_promo = PROMO_OPTS[square_idx as usize];
 
-                            promotion = Some(Piece {
-                              class: selected_promo,
-                              color: to_move,
-                            });
+                            promotion = Some(selected_promo);
                             break 'pawn;
                           }
                         }
@@ -1414,31 +577,18 @@ fn main() {
                   }
                 }
 
-                let mv = dbg!(Move {
-                  from: (ox, oy),
-                  to: (xn, yn),
-                  promotion,
-                });
-
-                let board_after_move = || board.apply_move(mv);
-
-                if dbg!(is_move_legal(&board, mv))
-                  && !dbg!(is_in_check(&board_after_move(), to_move))
-                {
-                  board = board_after_move();
-
-                  board_states.push(board);
-
-                  println!("({ox}, {oy}) -> ({x}, {y})");
-
-                  to_move = !to_move;
+                if let Some(&mv) = candidates.iter().find(|mv| mv.promotion() == promotion) {
+                  println!("{}", mv.to_san(&board));
+                  game.push(mv);
+                  board = *game.board();
 
                   // gg
-                  if is_in_checkmate(&board, to_move) {
-                    print_checkmate(!to_move);
+                  print_status(&game);
+                  if game.status().is_over() {
+                    archive_game(&mut game, engine_sides, &mut archived);
                   }
 
-                  println!("{:?}", to_move);
+                  println!("{:?}", board.side_to_move());
                   // println!("{to_move:?} in check? {}", is_in_check(&board, to_move));
                 } else {
                   println!("Illegal move!");
@@ -1457,11 +607,6 @@ fn main() {
 
     window.display()
 
-    // missing chess rules:
-    // -50 move rule
-    // -stalemate
-    // -draw by repetition
-
     // missing features:
     // -undo move
   }
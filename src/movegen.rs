@@ -0,0 +1,355 @@
+use crate::attacks::*;
+use crate::bitboard::Bitboard;
+use crate::board::{Board, Move, MoveKind};
+use crate::movelist::MoveList;
+use crate::piece::PieceType::*;
+use crate::piece::*;
+
+#[inline(always)]
+pub const fn to_offset(x: i32, y: i32) -> i32 {
+  8 * y + x
+}
+
+#[inline(always)]
+pub const fn to_coord(idx: u32) -> (u32, u32) {
+  (idx % 8, idx / 8)
+}
+
+// side to move is in check and has no legal moves
+pub fn is_in_checkmate(board: &Board) -> bool {
+  is_in_check(board, board.side_to_move()) && !has_legal_move(board)
+}
+
+// side to move is not in check but has no legal moves
+pub fn is_in_stalemate(board: &Board) -> bool {
+  !is_in_check(board, board.side_to_move()) && !has_legal_move(board)
+}
+
+pub fn has_legal_move(board: &Board) -> bool {
+  !legal_moves(board).is_empty()
+}
+
+// whether a pseudo-legal move for the side to move doesn't leave its own
+// king in check; the board is back in its original state afterwards
+pub(crate) fn leaves_king_safe(board: &mut Board, mv: Move) -> bool {
+  let player = board.side_to_move();
+  let undo = board.make_move(mv);
+  let safe = !is_in_check(board, player);
+  board.unmake_move(undo);
+  safe
+}
+
+pub fn is_in_check(board: &Board, player: PieceColor) -> bool {
+  is_square_attacked(board, to_coord(board.king_square(player)), !player)
+}
+
+// Whether any piece of color `by` attacks (x, y). Unlike going through
+// moves_for_piece this never generates castling moves, so it is safe to
+// call from castling generation itself.
+pub fn is_square_attacked(board: &Board, square: (u32, u32), by: PieceColor) -> bool {
+  board.is_attacked(square, by)
+}
+
+// pieces of color `by` attacking `sq`, with sliders looking through
+// `occupied` rather than the board's actual occupancy
+pub(crate) fn attackers(board: &Board, sq: u32, by: PieceColor, occupied: Bitboard) -> Bitboard {
+  let diagonal = board.pieces(Bishop, by) | board.pieces(Queen, by);
+  let straight = board.pieces(Rook, by) | board.pieces(Queen, by);
+
+  // a pawn of color `by` attacks sq from exactly the squares an opposing
+  // pawn on sq would attack
+  (pawn_attacks(!by, sq) & board.pieces(Pawn, by))
+    | (knight_attacks(sq) & board.pieces(Knight, by))
+    | (king_attacks(sq) & board.pieces(King, by))
+    | (bishop_attacks(sq, occupied) & diagonal)
+    | (rook_attacks(sq, occupied) & straight)
+}
+
+/// Whether `color` may castle towards the rook on the left or right (in terms
+/// of the x-axis) right now: the right hasn't been lost, the king and rook are
+/// on their home squares, every square between them is empty, and the king is
+/// not in check and does not pass through or land on an attacked square.
+pub fn can_castle(board: &Board, color: PieceColor, is_rook_right: bool) -> bool {
+  let rank = if color.is_white() { 7 } else { 0 };
+  let rook_x = if is_rook_right { 7 } else { 0 };
+
+  // squares between king and rook, and squares the king stands on or crosses
+  let (between, king_path) = if is_rook_right {
+    ([5, 6].as_slice(), [4, 5, 6])
+  } else {
+    ([1, 2, 3].as_slice(), [4, 3, 2])
+  };
+
+  let between: Bitboard = between.iter().map(|&x| 8 * rank + x).collect();
+
+  board.castling_rights[color as usize][is_rook_right as usize]
+    && board[(4, rank)] == Some(Piece { class: King, color })
+    && board[(rook_x, rank)] == Some(Piece { class: Rook, color })
+    && (board.occupied() & between).is_empty()
+    && king_path
+      .iter()
+      .all(|&x| !is_square_attacked(board, (x, rank), !color))
+}
+
+#[inline(always)]
+pub fn inbounds(x: i32, y: i32) -> bool {
+  (0..=7).contains(&x) && (0..=7).contains(&y)
+}
+
+// pushes a pawn move, expanding it into one move per promotion piece
+// (queen and all underpromotions) if it reaches the last rank
+#[inline(always)]
+fn push_pawn_move(moves: &mut MoveList, from: u32, to: u32, color: PieceColor, capture: bool) {
+  let last_rank = if color.is_white() { 0 } else { 7 };
+
+  if to / 8 == last_rank {
+    for class in PROMO_OPTS {
+      let kind = if capture {
+        MoveKind::PromotionCapture(class)
+      } else {
+        MoveKind::Promotion(class)
+      };
+      moves.push(Move::new(from, to, kind));
+    }
+  } else {
+    let kind = if capture {
+      MoveKind::Capture
+    } else {
+      MoveKind::Quiet
+    };
+    moves.push(Move::new(from, to, kind));
+  }
+}
+
+// quiet moves and captures from `from` to each square in `targets`
+#[inline(always)]
+fn push_moves(moves: &mut MoveList, from: u32, targets: Bitboard, enemy: Bitboard) {
+  for to in targets {
+    let kind = if enemy.contains(to) {
+      MoveKind::Capture
+    } else {
+      MoveKind::Quiet
+    };
+    moves.push(Move::new(from, to, kind));
+  }
+}
+
+// pawn pushes and captures (but not en passant) from `sq` that land on a
+// square in `allowed`
+#[inline(always)]
+fn push_pawn_moves(
+  board: &Board,
+  sq: u32,
+  color: PieceColor,
+  allowed: Bitboard,
+  moves: &mut MoveList,
+) {
+  let enemy = board.color_pieces(!color);
+  let occupied = board.occupied();
+  let direction = color.direction();
+
+  // basic move, push forward 1
+  let one = (sq as i32 + 8 * direction) as u32;
+  if !occupied.contains(one) {
+    if allowed.contains(one) {
+      push_pawn_move(moves, sq, one, color, false);
+    }
+
+    // push 2 if on rank 2
+    let push2_rank = if color.is_white() { 6 } else { 1 };
+    let two = (sq as i32 + 16 * direction) as u32;
+    if sq / 8 == push2_rank && !occupied.contains(two) && allowed.contains(two) {
+      moves.push(Move::new(sq, two, MoveKind::DoublePawnPush));
+    }
+  }
+
+  // sideways attacking moves
+  for to in pawn_attacks(color, sq) & enemy & allowed {
+    push_pawn_move(moves, sq, to, color, true);
+  }
+}
+
+// the en passant square as an index, if a pawn of `color` on `sq` can
+// capture there (ignoring checks)
+#[inline(always)]
+fn en_passant_target(board: &Board, sq: u32, color: PieceColor) -> Option<u32> {
+  board
+    .en_passant_square
+    .map(|(x, y)| 8 * y + x)
+    .filter(|&ep| pawn_attacks(color, sq).contains(ep))
+}
+
+// pseudo-legal moves for the piece on (x, y), including every promotion
+// choice and (fully legal) castling; does not check whether the move leaves
+// our own king in check.
+pub fn moves_for_piece(board: &Board, (x, y): (u32, u32)) -> MoveList {
+  let mut moves = MoveList::new();
+  push_piece_moves(board, 8 * y + x, &mut moves);
+  moves
+}
+
+// moves_for_piece, appending to an existing list
+pub(crate) fn push_piece_moves(board: &Board, sq: u32, moves: &mut MoveList) {
+  let p = match board[sq] {
+    Some(p) => p,
+    None => unreachable!("function should not be called on empty square"),
+  };
+
+  let own = board.color_pieces(p.color);
+  let enemy = board.color_pieces(!p.color);
+  let occupied = own | enemy;
+
+  match p.class {
+    PieceType::Pawn => {
+      push_pawn_moves(board, sq, p.color, !Bitboard::EMPTY, moves);
+      if let Some(ep) = en_passant_target(board, sq, p.color) {
+        moves.push(Move::new(sq, ep, MoveKind::EnPassant));
+      }
+    }
+    PieceType::King => {
+      push_moves(moves, sq, king_attacks(sq) & !own, enemy);
+      push_castles(board, sq, p.color, moves);
+    }
+    class => push_moves(moves, sq, piece_attacks(class, sq, occupied) & !own, enemy),
+  }
+}
+
+#[inline(always)]
+fn push_castles(board: &Board, king: u32, color: PieceColor, moves: &mut MoveList) {
+  let rank_yidx = if color.is_white() { 7u32 } else { 0 };
+  if king == 8 * rank_yidx + 4 {
+    if can_castle(board, color, false) {
+      moves.push(Move::new(king, king - 2, MoveKind::QueensideCastle));
+    }
+    if can_castle(board, color, true) {
+      moves.push(Move::new(king, king + 2, MoveKind::KingsideCastle));
+    }
+  }
+}
+
+/// All legal moves for the side to move.
+pub fn legal_moves(board: &Board) -> MoveList {
+  let mut moves = MoveList::new();
+  push_legal_moves(board, !Bitboard::EMPTY, &mut moves);
+  moves
+}
+
+/// Legal moves for the piece on (x, y); empty if it isn't the side to move's
+/// piece.
+pub fn legal_moves_for_piece(board: &Board, (x, y): (u32, u32)) -> MoveList {
+  let mut moves = MoveList::new();
+  push_legal_moves(board, Bitboard::from_coord((x, y)), &mut moves);
+  moves
+}
+
+// pieces of `color` that are the only thing between their king and an
+// enemy slider looking at it
+fn pinned_pieces(board: &Board, color: PieceColor, king: u32) -> Bitboard {
+  let them = !color;
+  let queens = board.pieces(Queen, them);
+  let snipers = (rook_attacks(king, Bitboard::EMPTY) & (board.pieces(Rook, them) | queens))
+    | (bishop_attacks(king, Bitboard::EMPTY) & (board.pieces(Bishop, them) | queens));
+
+  let occupied = board.occupied();
+  let mut pinned = Bitboard::EMPTY;
+  for sniper in snipers {
+    let blockers = between(king, sniper) & occupied;
+    if blockers.count() == 1 {
+      pinned |= blockers & board.color_pieces(color);
+    }
+  }
+  pinned
+}
+
+// legal moves of the side to move's pieces on the squares in `from`. Rather
+// than making every move and looking for check afterwards, this works out
+// the checking pieces and pinned pieces first and only generates moves that
+// respect them.
+fn push_legal_moves(board: &Board, from: Bitboard, moves: &mut MoveList) {
+  let us = board.side_to_move();
+  let them = !us;
+  let own = board.color_pieces(us);
+  let enemy = board.color_pieces(them);
+  let occupied = own | enemy;
+  let king = board.king_square(us);
+  let king_bb = Bitboard::from_square(king);
+
+  let checkers = attackers(board, king, them, occupied);
+
+  if from.contains(king) {
+    // the king is taken off the board when looking for attacks on its
+    // destination, otherwise it would block a slider's ray to the square
+    // right behind it
+    let without_king = occupied ^ king_bb;
+    for to in king_attacks(king) & !own {
+      if attackers(board, to, them, without_king).is_empty() {
+        let kind = if enemy.contains(to) {
+          MoveKind::Capture
+        } else {
+          MoveKind::Quiet
+        };
+        moves.push(Move::new(king, to, kind));
+      }
+    }
+
+    if checkers.is_empty() {
+      push_castles(board, king, us, moves);
+    }
+  }
+
+  // in double check only the king can move
+  if checkers.count() > 1 {
+    return;
+  }
+
+  // in single check every other move has to capture the checker or block it
+  let check_mask = match checkers.first() {
+    Some(checker) => checkers | between(king, checker),
+    None => !Bitboard::EMPTY,
+  };
+
+  let pinned = pinned_pieces(board, us, king);
+
+  for sq in own & from & !king_bb {
+    let piece = board[sq].unwrap();
+
+    // pinned pieces can only move along the pin
+    let allowed = if pinned.contains(sq) {
+      check_mask & line(king, sq)
+    } else {
+      check_mask
+    };
+
+    match piece.class {
+      PieceType::Pawn => {
+        push_pawn_moves(board, sq, us, allowed, moves);
+
+        // en passant removes two pieces from the capturing pawn's rank, which
+        // can expose the king in ways pins don't cover, so just check the
+        // position after the capture directly
+        if let Some(ep) = en_passant_target(board, sq, us) {
+          let captured = Bitboard::from_square((ep as i32 - 8 * us.direction()) as u32);
+          let after = (occupied ^ Bitboard::from_square(sq) ^ captured) | Bitboard::from_square(ep);
+          if (attackers(board, king, them, after) & !captured).is_empty() {
+            moves.push(Move::new(sq, ep, MoveKind::EnPassant));
+          }
+        }
+      }
+      class => push_moves(
+        moves,
+        sq,
+        piece_attacks(class, sq, occupied) & !own & allowed,
+        enemy,
+      ),
+    }
+  }
+}
+
+// whether `mv` is pseudo-legal: there is a piece on its starting square and
+// the move is one that piece can make (including castling legality and a
+// valid promotion), ignoring whether it leaves that side's king in check.
+pub fn is_move_legal(board: &Board, mv: Move) -> bool {
+  board[mv.from_sq()].is_some() && moves_for_piece(board, mv.from()).contains(&mv)
+}
+
+pub const PROMO_OPTS: [PieceType; 4] = [Knight, Bishop, Rook, Queen];
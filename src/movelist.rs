@@ -0,0 +1,120 @@
+use std::fmt;
+use std::ops::Deref;
+
+use crate::board::Move;
+
+// no legal position has more than 218 moves
+const CAPACITY: usize = 256;
+
+/// Fixed-capacity list of moves stored inline, so that generating moves
+/// never allocates. Derefs to a slice for everything read-only.
+#[derive(Copy, Clone)]
+pub struct MoveList {
+  moves: [Move; CAPACITY],
+  len: usize,
+}
+
+impl MoveList {
+  #[inline]
+  pub const fn new() -> Self {
+    Self {
+      moves: [Move::NULL; CAPACITY],
+      len: 0,
+    }
+  }
+
+  #[inline(always)]
+  pub fn push(&mut self, mv: Move) {
+    self.moves[self.len] = mv;
+    self.len += 1;
+  }
+
+  /// Keep only the moves for which `f` returns true, preserving their order.
+  pub fn retain(&mut self, mut f: impl FnMut(Move) -> bool) {
+    let mut kept = 0;
+    for i in 0..self.len {
+      let mv = self.moves[i];
+      if f(mv) {
+        self.moves[kept] = mv;
+        kept += 1;
+      }
+    }
+    self.len = kept;
+  }
+
+  #[inline]
+  pub fn clear(&mut self) {
+    self.len = 0;
+  }
+}
+
+impl Default for MoveList {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Deref for MoveList {
+  type Target = [Move];
+
+  #[inline(always)]
+  fn deref(&self) -> &[Move] {
+    &self.moves[..self.len]
+  }
+}
+
+impl fmt::Debug for MoveList {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+impl Extend<Move> for MoveList {
+  fn extend<I: IntoIterator<Item = Move>>(&mut self, iter: I) {
+    for mv in iter {
+      self.push(mv);
+    }
+  }
+}
+
+impl<'a> IntoIterator for &'a MoveList {
+  type Item = &'a Move;
+  type IntoIter = std::slice::Iter<'a, Move>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+/// Owning iterator over a `MoveList`.
+pub struct IntoIter {
+  list: MoveList,
+  idx: usize,
+}
+
+impl Iterator for IntoIter {
+  type Item = Move;
+
+  #[inline]
+  fn next(&mut self) -> Option<Move> {
+    let mv = self.list.get(self.idx).copied();
+    self.idx += 1;
+    mv
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let n = self.list.len.saturating_sub(self.idx);
+    (n, Some(n))
+  }
+}
+
+impl ExactSizeIterator for IntoIter {}
+
+impl IntoIterator for MoveList {
+  type Item = Move;
+  type IntoIter = IntoIter;
+
+  fn into_iter(self) -> IntoIter {
+    IntoIter { list: self, idx: 0 }
+  }
+}
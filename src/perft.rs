@@ -0,0 +1,49 @@
+use crate::board::{Board, Move};
+
+/// Count the leaf nodes of the legal move tree `depth` plies deep.
+pub fn perft(board: &Board, depth: u32) -> u64 {
+  let mut board = *board;
+  perft_inner(&mut board, depth)
+}
+
+fn perft_inner(board: &mut Board, depth: u32) -> u64 {
+  if depth == 0 {
+    return 1;
+  }
+
+  let moves = board.moves_for_player();
+
+  // bulk counting: no need to make the last move just to count it
+  if depth == 1 {
+    return moves.len() as u64;
+  }
+
+  moves
+    .into_iter()
+    .map(|mv| {
+      let undo = board.make_move(mv);
+      let nodes = perft_inner(board, depth - 1);
+      board.unmake_move(undo);
+      nodes
+    })
+    .sum()
+}
+
+/// Perft split by root move, for narrowing down which move a
+/// generator bug is under.
+pub fn divide(board: &Board, depth: u32) -> Vec<(Move, u64)> {
+  assert!(depth >= 1, "divide needs at least one ply");
+
+  let mut board = *board;
+
+  board
+    .moves_for_player()
+    .into_iter()
+    .map(|mv| {
+      let undo = board.make_move(mv);
+      let nodes = perft_inner(&mut board, depth - 1);
+      board.unmake_move(undo);
+      (mv, nodes)
+    })
+    .collect()
+}
@@ -0,0 +1,882 @@
+use std::error::Error;
+use std::fmt::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::board::{Board, Move};
+use crate::fen::{FenError, STARTING_FEN};
+use crate::san::SanError;
+use crate::status::{GameResult, GameStatus};
+
+/// Longest line PGN export format allows.
+pub const PGN_LINE_LENGTH: usize = 79;
+
+/// The Seven Tag Roster, in the order the standard requires them to be
+/// exported.
+pub const SEVEN_TAG_ROSTER: [&str; 7] =
+  ["Event", "Site", "Date", "Round", "White", "Black", "Result"];
+
+/// What a PGN file says about a move besides the move itself.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Annotation {
+  /// Numeric Annotation Glyphs, in order. Move suffixes are read as their
+  /// standard NAGs: `!` as 1, `?` as 2, `!!` as 3, `??` as 4, `!?` as 5 and
+  /// `?!` as 6.
+  pub nags: Vec<u8>,
+  /// Comments following the move, with runs of whitespace in them turned
+  /// into single spaces.
+  pub comments: Vec<String>,
+  /// Alternatives to the move, played from the position before it.
+  pub variations: Vec<Variation>,
+}
+
+/// A line of play other than the game's own. Its moves can have annotations
+/// of their own, variations included.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Variation {
+  /// Comments before the first move.
+  pub comments: Vec<String>,
+  pub moves: Vec<(Move, Annotation)>,
+}
+
+/// A game as it was played: the starting position, the moves made from it,
+/// and the PGN tags describing it, with any comments, NAGs and variations
+/// read along with the moves.
+///
+/// Every position along the way is kept too, both for repetition detection
+/// and so a move can be taken back without replaying the game.
+#[derive(Clone, Debug)]
+pub struct Game {
+  tags: Vec<(String, String)>,
+  // comments before the first move
+  comments: Vec<String>,
+  moves: Vec<Move>,
+  // annotations[i] belongs to moves[i]
+  annotations: Vec<Annotation>,
+  // positions[0] is the starting position, positions[i + 1] follows moves[i]
+  positions: Vec<Board>,
+  // resignation, agreed draw, loss on time...
+  declared_result: Option<GameResult>,
+}
+
+impl Game {
+  /// A game from the standard starting position.
+  pub fn new() -> Self {
+    Self::from_position(Board::new())
+  }
+
+  pub fn from_position(start: Board) -> Self {
+    Game {
+      tags: Vec::new(),
+      comments: Vec::new(),
+      moves: Vec::new(),
+      annotations: Vec::new(),
+      positions: vec![start],
+      declared_result: None,
+    }
+  }
+
+  pub fn start(&self) -> &Board {
+    &self.positions[0]
+  }
+
+  /// The current position.
+  pub fn board(&self) -> &Board {
+    self.positions.last().unwrap()
+  }
+
+  pub fn moves(&self) -> &[Move] {
+    &self.moves
+  }
+
+  /// Every position of the game, starting position first and the current
+  /// one last.
+  pub fn positions(&self) -> &[Board] {
+    &self.positions
+  }
+
+  /// Comments before the first move.
+  pub fn comments(&self) -> &[String] {
+    &self.comments
+  }
+
+  pub fn set_comments(&mut self, comments: Vec<String>) {
+    self.comments = comments;
+  }
+
+  /// The annotation of every move, in the order of [`moves`](Game::moves).
+  pub fn annotations(&self) -> &[Annotation] {
+    &self.annotations
+  }
+
+  /// The annotation of the move at index `ply`, if there is one.
+  pub fn annotation_mut(&mut self, ply: usize) -> Option<&mut Annotation> {
+    self.annotations.get_mut(ply)
+  }
+
+  /// Play `mv`, which has to be legal in the current position.
+  pub fn push(&mut self, mv: Move) {
+    debug_assert!(self.board().moves_for_player().contains(&mv));
+
+    let next = self.board().apply_move(mv);
+    self.moves.push(mv);
+    self.annotations.push(Annotation::default());
+    self.positions.push(next);
+  }
+
+  /// Take back the last move, if there is one.
+  pub fn pop(&mut self) -> Option<Move> {
+    let mv = self.moves.pop()?;
+    self.annotations.pop();
+    self.positions.pop();
+    self.declared_result = None;
+    Some(mv)
+  }
+
+  pub fn status(&self) -> GameStatus {
+    let (current, history) = self.positions.split_last().unwrap();
+    current.status(history)
+  }
+
+  /// Result of the game as it stands, `None` while it's still going.
+  /// Checkmate, stalemate and the draws that end a game by themselves
+  /// decide it; otherwise this is the declared result. A threefold
+  /// repetition or fifty-move draw only counts once declared, since a
+  /// player has to claim it.
+  pub fn result(&self) -> Option<GameResult> {
+    let status = self.status();
+    if status.is_over() && !status.is_claimable() {
+      status.result()
+    } else {
+      self.declared_result
+    }
+  }
+
+  /// Record a result decided away from the board, such as a resignation
+  /// or an agreed draw.
+  pub fn set_declared_result(&mut self, result: Option<GameResult>) {
+    self.declared_result = result;
+  }
+
+  /// Value of the tag `name`, if it was set.
+  pub fn tag(&self, name: &str) -> Option<&str> {
+    self
+      .tags
+      .iter()
+      .find(|(tag, _)| tag == name)
+      .map(|(_, value)| value.as_str())
+  }
+
+  /// Set tag `name`, replacing any previous value. `Result`, `SetUp` and
+  /// `FEN` are ignored on export: the result comes from
+  /// [`result`](Game::result), and the other two are written whenever the game didn't
+  /// start from the standard position.
+  pub fn set_tag(&mut self, name: &str, value: &str) {
+    match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+      Some((_, old)) => *old = value.to_string(),
+      None => self.tags.push((name.to_string(), value.to_string())),
+    }
+  }
+
+  /// The game in PGN export format: the Seven Tag Roster (with `?` for
+  /// anything unknown), any other tags, and the movetext in SAN wrapped to
+  /// [`PGN_LINE_LENGTH`], ending with the result. Suffixes such as `!` are
+  /// written as NAGs, and comments holding a `}` as `;` comments.
+  pub fn to_pgn(&self) -> String {
+    let result = result_token(self.result());
+
+    let mut pgn = String::new();
+    for name in SEVEN_TAG_ROSTER {
+      let value = match name {
+        "Result" => result,
+        "Date" => self.tag(name).unwrap_or("????.??.??"),
+        _ => self.tag(name).unwrap_or("?"),
+      };
+      write_tag(&mut pgn, name, value);
+    }
+
+    let fen = self.start().to_fen();
+    if fen != STARTING_FEN {
+      write_tag(&mut pgn, "SetUp", "1");
+      write_tag(&mut pgn, "FEN", &fen);
+    }
+
+    for (name, value) in &self.tags {
+      if !SEVEN_TAG_ROSTER.contains(&name.as_str()) && !matches!(name.as_str(), "SetUp" | "FEN") {
+        write_tag(&mut pgn, name, value);
+      }
+    }
+
+    pgn.push('\n');
+    pgn.push_str(&wrap(&self.movetext(result)));
+    pgn.push('\n');
+    pgn
+  }
+
+  fn movetext(&self, result: &str) -> Vec<String> {
+    let mut tokens = Vec::with_capacity(self.moves.len() * 3 / 2 + 1);
+    let mut moves = self.moves.iter().copied().zip(&self.annotations);
+    line_tokens(&mut tokens, *self.start(), &self.comments, &mut moves);
+    tokens.push(result.to_string());
+    tokens
+  }
+}
+
+// the movetext of `moves` played from `start`, annotations and all
+fn line_tokens(
+  tokens: &mut Vec<String>,
+  start: Board,
+  comments: &[String],
+  moves: &mut dyn Iterator<Item = (Move, &Annotation)>,
+) {
+  comment_tokens(tokens, comments);
+
+  let mut board = start;
+  // Black's moves get a number of their own at the start and wherever
+  // a comment or variation came in between
+  let mut interrupted = true;
+  for (mv, annotation) in moves {
+    let number = board.fullmove_number();
+    if board.side_to_move().is_white() {
+      tokens.push(format!("{number}."));
+    } else if interrupted {
+      tokens.push(format!("{number}..."));
+    }
+    tokens.push(mv.to_san(&board));
+
+    tokens.extend(annotation.nags.iter().map(|nag| format!("${nag}")));
+    comment_tokens(tokens, &annotation.comments);
+    for variation in &annotation.variations {
+      let mut inner = vec!["(".to_string()];
+      line_tokens(
+        &mut inner,
+        board,
+        &variation.comments,
+        &mut variation
+          .moves
+          .iter()
+          .map(|(mv, annotation)| (*mv, annotation)),
+      );
+      inner.push(")".to_string());
+      tokens.extend(glue_parens(inner));
+    }
+
+    interrupted = !annotation.comments.is_empty() || !annotation.variations.is_empty();
+    board = board.apply_move(mv);
+  }
+}
+
+// "(" and ")" stuck to the tokens next to them, unless that would put them
+// in a `;` comment
+fn glue_parens(tokens: Vec<String>) -> Vec<String> {
+  let mut glued: Vec<String> = Vec::with_capacity(tokens.len());
+  for token in tokens {
+    match glued.last_mut() {
+      Some(last) if last.ends_with('(') && !token.starts_with(';') => last.push_str(&token),
+      Some(last) if token == ")" && !last.starts_with(';') => last.push(')'),
+      _ => glued.push(token),
+    }
+  }
+  glued
+}
+
+// a comment word by word so it can be wrapped, or as a `;` comment to the
+// end of the line if a `}` would cut a braced one short
+fn comment_tokens(tokens: &mut Vec<String>, comments: &[String]) {
+  for comment in comments {
+    if comment.contains('}') {
+      tokens.push(format!(";{comment}"));
+      continue;
+    }
+
+    let mut words: Vec<String> = comment.split_whitespace().map(str::to_string).collect();
+    match words.as_mut_slice() {
+      [] => tokens.push("{}".to_string()),
+      [first, .., last] => {
+        first.insert(0, '{');
+        last.push('}');
+      }
+      [only] => *only = format!("{{{only}}}"),
+    }
+    tokens.append(&mut words);
+  }
+}
+
+impl Default for Game {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// The movetext token for `result`: `1-0`, `0-1`, `1/2-1/2`, or `*` for a
+/// game that isn't over.
+pub fn result_token(result: Option<GameResult>) -> &'static str {
+  match result {
+    Some(GameResult::WhiteWins) => "1-0",
+    Some(GameResult::BlackWins) => "0-1",
+    Some(GameResult::Draw) => "1/2-1/2",
+    None => "*",
+  }
+}
+
+fn write_tag(pgn: &mut String, name: &str, value: &str) {
+  let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+  writeln!(pgn, "[{name} \"{value}\"]").unwrap();
+}
+
+// join tokens with spaces, breaking lines before they get too long and
+// after `;` comments, which run to the end of the line
+fn wrap(tokens: &[String]) -> String {
+  let mut text = String::new();
+  let mut line_len = 0;
+
+  for token in tokens {
+    if line_len > 0 && line_len + 1 + token.len() > PGN_LINE_LENGTH {
+      text.push('\n');
+      line_len = 0;
+    } else if line_len > 0 {
+      text.push(' ');
+      line_len += 1;
+    }
+    text.push_str(token);
+    line_len += token.len();
+
+    if token.starts_with(';') {
+      text.push('\n');
+      line_len = 0;
+    }
+  }
+
+  text.push('\n');
+  text
+}
+
+/// `time` as a PGN `Date` tag value, `YYYY.MM.DD` in UTC.
+pub fn pgn_date(time: SystemTime) -> String {
+  let days = time
+    .duration_since(UNIX_EPOCH)
+    .map_or(0, |d| d.as_secs() / 86400) as i64;
+
+  // days since 1970-01-01 to a civil date (Howard Hinnant's algorithm)
+  let z = days + 719468;
+  let era = z.div_euclid(146097);
+  let doe = z.rem_euclid(146097);
+  let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = doy - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = yoe + era * 400 + (month <= 2) as i64;
+
+  format!("{year:04}.{month:02}.{day:02}")
+}
+
+/// What went wrong reading PGN, and where: `line` and `column` count from 1,
+/// columns in characters.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PgnError {
+  pub line: usize,
+  pub column: usize,
+  pub kind: PgnErrorKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PgnErrorKind {
+  /// Text that doesn't belong where it appears.
+  UnexpectedToken(String),
+  UnexpectedEof,
+  UnterminatedComment,
+  UnterminatedString,
+  UnterminatedVariation,
+  /// A variation that doesn't follow any move it could replace.
+  MisplacedVariation,
+  /// The `FEN` tag doesn't hold a valid position.
+  InvalidFen(FenError),
+  /// A move that can't be played in the position it appears in.
+  IllegalMove(SanError),
+}
+
+impl fmt::Display for PgnError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "line {}, column {}: {}",
+      self.line, self.column, self.kind
+    )
+  }
+}
+
+impl fmt::Display for PgnErrorKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      PgnErrorKind::UnexpectedToken(token) => write!(f, "unexpected '{token}'"),
+      PgnErrorKind::UnexpectedEof => f.write_str("unexpected end of input"),
+      PgnErrorKind::UnterminatedComment => f.write_str("comment is never closed"),
+      PgnErrorKind::UnterminatedString => f.write_str("string is never closed"),
+      PgnErrorKind::UnterminatedVariation => f.write_str("variation is never closed"),
+      PgnErrorKind::MisplacedVariation => f.write_str("variation doesn't follow a move"),
+      PgnErrorKind::InvalidFen(err) => write!(f, "bad FEN tag: {err}"),
+      PgnErrorKind::IllegalMove(err) => err.fmt(f),
+    }
+  }
+}
+
+impl Error for PgnError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+  LBracket,
+  RBracket,
+  LParen,
+  RParen,
+  Period,
+  Asterisk,
+  Str(String),
+  Symbol(String),
+  Comment(String),
+  Nag(u8),
+}
+
+// 1-based line and column
+type Pos = (usize, usize);
+
+struct Lexer {
+  chars: Vec<char>,
+  idx: usize,
+  line: usize,
+  column: usize,
+  peeked: Option<(Token, Pos)>,
+}
+
+impl Lexer {
+  fn new(text: &str) -> Self {
+    Lexer {
+      chars: text.chars().collect(),
+      idx: 0,
+      line: 1,
+      column: 1,
+      peeked: None,
+    }
+  }
+
+  fn error(pos: Pos, kind: PgnErrorKind) -> PgnError {
+    PgnError {
+      line: pos.0,
+      column: pos.1,
+      kind,
+    }
+  }
+
+  // carry on from the start of `line`
+  fn seek_line(&mut self, line: usize) {
+    self.peeked = None;
+    // having peeked at a token, the lexer may already be on `line`
+    if self.line >= line {
+      (self.idx, self.line, self.column) = (0, 1, 1);
+    }
+    while self.line < line && self.bump().is_some() {}
+  }
+
+  fn bump(&mut self) -> Option<char> {
+    let c = *self.chars.get(self.idx)?;
+    self.idx += 1;
+    if c == '\n' {
+      self.line += 1;
+      self.column = 1;
+    } else {
+      self.column += 1;
+    }
+    Some(c)
+  }
+
+  fn peek_char(&self) -> Option<char> {
+    self.chars.get(self.idx).copied()
+  }
+
+  fn peek(&mut self) -> Result<Option<&(Token, Pos)>, PgnError> {
+    if self.peeked.is_none() {
+      self.peeked = self.lex()?;
+    }
+    Ok(self.peeked.as_ref())
+  }
+
+  fn next(&mut self) -> Result<Option<(Token, Pos)>, PgnError> {
+    match self.peeked.take() {
+      Some(token) => Ok(Some(token)),
+      None => self.lex(),
+    }
+  }
+
+  // the next token, skipping whitespace and escaped lines
+  fn lex(&mut self) -> Result<Option<(Token, Pos)>, PgnError> {
+    loop {
+      let pos = (self.line, self.column);
+      let Some(c) = self.bump() else {
+        return Ok(None);
+      };
+
+      let token = match c {
+        c if c.is_whitespace() => continue,
+        // a '%' in the first column escapes the whole line
+        '%' if pos.1 == 1 => {
+          self.skip_line();
+          continue;
+        }
+        ';' => {
+          let mut text = String::new();
+          while let Some(c) = self.bump().filter(|&c| c != '\n') {
+            text.push(c);
+          }
+          Token::Comment(normalize_comment(&text))
+        }
+        '{' => {
+          let mut text = String::new();
+          loop {
+            match self.bump() {
+              Some('}') => break,
+              Some(c) => text.push(c),
+              None => return Err(Self::error(pos, PgnErrorKind::UnterminatedComment)),
+            }
+          }
+          Token::Comment(normalize_comment(&text))
+        }
+        '$' => {
+          let digits = self.take_while(|c| c.is_ascii_digit());
+          match digits.parse() {
+            Ok(nag) => Token::Nag(nag),
+            Err(_) => {
+              return Err(Self::error(
+                pos,
+                PgnErrorKind::UnexpectedToken(format!("${digits}")),
+              ))
+            }
+          }
+        }
+        // annotation glyphs set apart from their move
+        '!' | '?' => {
+          let suffix = format!("{c}{}", self.take_while(|c| matches!(c, '!' | '?')));
+          match suffix_nag(&suffix) {
+            Some(nag) => Token::Nag(nag),
+            None => continue,
+          }
+        }
+        '[' => Token::LBracket,
+        ']' => Token::RBracket,
+        '(' => Token::LParen,
+        ')' => Token::RParen,
+        '.' => Token::Period,
+        '*' => Token::Asterisk,
+        '"' => Token::Str(self.string(pos)?),
+        c if c.is_ascii_alphanumeric() => {
+          let rest = self.take_while(|c| c.is_ascii_alphanumeric() || "_+#=:-/!?".contains(c));
+          Token::Symbol(format!("{c}{rest}"))
+        }
+        c => {
+          return Err(Self::error(
+            pos,
+            PgnErrorKind::UnexpectedToken(c.to_string()),
+          ))
+        }
+      };
+
+      return Ok(Some((token, pos)));
+    }
+  }
+
+  fn skip_line(&mut self) {
+    while let Some(c) = self.bump() {
+      if c == '\n' {
+        break;
+      }
+    }
+  }
+
+  fn take_while(&mut self, f: impl Fn(char) -> bool) -> String {
+    let mut s = String::new();
+    while let Some(c) = self.peek_char().filter(|&c| f(c)) {
+      s.push(c);
+      self.bump();
+    }
+    s
+  }
+
+  // the rest of a string whose opening quote was at `start`
+  fn string(&mut self, start: Pos) -> Result<String, PgnError> {
+    let mut s = String::new();
+    loop {
+      match self.bump() {
+        Some('"') => return Ok(s),
+        Some('\\') => match self.bump() {
+          Some(c) => s.push(c),
+          None => break,
+        },
+        Some('\n') | None => break,
+        Some(c) => s.push(c),
+      }
+    }
+    Err(Self::error(start, PgnErrorKind::UnterminatedString))
+  }
+
+  fn expect(&mut self, what: fn(&Token) -> bool) -> Result<(Token, Pos), PgnError> {
+    match self.next()? {
+      Some((token, pos)) if what(&token) => Ok((token, pos)),
+      Some((token, pos)) => Err(Self::error(
+        pos,
+        PgnErrorKind::UnexpectedToken(token_text(&token)),
+      )),
+      None => Err(Self::error(
+        (self.line, self.column),
+        PgnErrorKind::UnexpectedEof,
+      )),
+    }
+  }
+}
+
+fn token_text(token: &Token) -> String {
+  match token {
+    Token::LBracket => "[".to_string(),
+    Token::RBracket => "]".to_string(),
+    Token::LParen => "(".to_string(),
+    Token::RParen => ")".to_string(),
+    Token::Period => ".".to_string(),
+    Token::Asterisk => "*".to_string(),
+    Token::Str(s) => format!("\"{s}\""),
+    Token::Symbol(s) => s.clone(),
+    Token::Comment(s) => format!("{{{s}}}"),
+    Token::Nag(nag) => format!("${nag}"),
+  }
+}
+
+fn normalize_comment(text: &str) -> String {
+  text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// the NAG a move suffix such as "!?" stands for
+fn suffix_nag(suffix: &str) -> Option<u8> {
+  Some(match suffix {
+    "!" => 1,
+    "?" => 2,
+    "!!" => 3,
+    "??" => 4,
+    "!?" => 5,
+    "?!" => 6,
+    _ => return None,
+  })
+}
+
+// `Some(result)` for a game termination marker
+fn parse_result(token: &Token) -> Option<Option<GameResult>> {
+  match token {
+    Token::Asterisk => Some(None),
+    Token::Symbol(s) => match s.as_str() {
+      "1-0" => Some(Some(GameResult::WhiteWins)),
+      "0-1" => Some(Some(GameResult::BlackWins)),
+      "1/2-1/2" => Some(Some(GameResult::Draw)),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+/// Read every game in `text`, a PGN file with any number of games.
+///
+/// Moves are replayed from the starting position (the `FEN` tag if there is
+/// one), and so are the moves of variations, from the position they branch
+/// off at; the first move that isn't legal is an error. Comments, NAGs and
+/// variations are kept with the moves they follow (see [`Annotation`]);
+/// comments among the tags or before the first move go to
+/// [`Game::comments`].
+pub fn parse_pgn(text: &str) -> Result<Vec<Game>, PgnError> {
+  let mut lexer = Lexer::new(text);
+  let mut games = Vec::new();
+
+  while let Some(game) = read_game(&mut lexer)? {
+    games.push(game);
+  }
+
+  Ok(games)
+}
+
+/// Like [`parse_pgn`], but a bad game doesn't stop the others from being
+/// read: each game comes back as its own result, in the order of the file.
+///
+/// After an error, reading picks up again at the next line starting with
+/// `[` that follows the bad game's movetext, i.e. at the next game's tags.
+pub fn parse_pgn_games(text: &str) -> Vec<Result<Game, PgnError>> {
+  let mut lexer = Lexer::new(text);
+  let mut games = Vec::new();
+
+  loop {
+    match read_game(&mut lexer) {
+      Ok(Some(game)) => games.push(Ok(game)),
+      Ok(None) => break,
+      Err(err) => {
+        let resume = next_game_line(text, err.line);
+        games.push(Err(err));
+        match resume {
+          Some(line) => lexer.seek_line(line),
+          None => break,
+        }
+      }
+    }
+  }
+
+  games
+}
+
+// the first line after `line` that starts a game's tags, skipping any tags
+// left of the game `line` is in
+fn next_game_line(text: &str, line: usize) -> Option<usize> {
+  let mut in_tags = text.lines().nth(line - 1)?.starts_with('[');
+  for (i, text) in text.lines().enumerate().skip(line) {
+    if text.starts_with('[') {
+      if !in_tags {
+        return Some(i + 1);
+      }
+    } else if !text.trim().is_empty() {
+      in_tags = false;
+    }
+  }
+  None
+}
+
+// the next game, or `None` at the end of the input
+fn read_game(lexer: &mut Lexer) -> Result<Option<Game>, PgnError> {
+  let mut tags = Vec::new();
+  let mut comments = Vec::new();
+  let mut start = Board::new();
+
+  loop {
+    match lexer.peek()? {
+      Some((Token::LBracket, _)) => {}
+      Some((Token::Comment(_), _)) => {
+        let Some((Token::Comment(text), _)) = lexer.next()? else {
+          unreachable!()
+        };
+        comments.push(text);
+        continue;
+      }
+      // nothing but comments after the last game
+      None if tags.is_empty() => return Ok(None),
+      _ => break,
+    }
+
+    lexer.next()?;
+    let (Token::Symbol(name), _) = lexer.expect(|t| matches!(t, Token::Symbol(_)))? else {
+      unreachable!()
+    };
+    let (Token::Str(value), pos) = lexer.expect(|t| matches!(t, Token::Str(_)))? else {
+      unreachable!()
+    };
+    lexer.expect(|t| *t == Token::RBracket)?;
+
+    if name == "FEN" {
+      start =
+        Board::from_fen(&value).map_err(|err| Lexer::error(pos, PgnErrorKind::InvalidFen(err)))?;
+    }
+    tags.push((name, value));
+  }
+
+  let mut game = Game::from_position(start);
+  for (name, value) in &tags {
+    game.set_tag(name, value);
+  }
+
+  let (line, result) = read_line(lexer, start, None)?;
+  comments.extend(line.comments);
+  game.comments = comments;
+  for (mv, annotation) in line.moves {
+    game.push(mv);
+    *game.annotations.last_mut().unwrap() = annotation;
+  }
+  game.set_declared_result(result);
+
+  Ok(Some(game))
+}
+
+// Moves of the main line (`opened_at` is `None`) or of a variation opened by
+// the parenthesis at `opened_at`, played from `start`, with their
+// annotations. The main line ends at its result, the next game's tags or
+// the end of input; the result, if there was one, is returned with the
+// moves.
+fn read_line(
+  lexer: &mut Lexer,
+  start: Board,
+  opened_at: Option<Pos>,
+) -> Result<(Variation, Option<GameResult>), PgnError> {
+  let mut line = Variation::default();
+  let mut positions = vec![start];
+
+  loop {
+    let Some((token, pos)) = lexer.peek()?.cloned() else {
+      return match opened_at {
+        None => Ok((line, None)),
+        Some(open) => Err(Lexer::error(open, PgnErrorKind::UnterminatedVariation)),
+      };
+    };
+
+    // the end of the game
+    if token == Token::LBracket || parse_result(&token).is_some() {
+      if let Some(open) = opened_at {
+        return Err(Lexer::error(open, PgnErrorKind::UnterminatedVariation));
+      }
+      if token == Token::LBracket {
+        return Ok((line, None));
+      }
+      lexer.next()?;
+      return Ok((line, parse_result(&token).unwrap()));
+    }
+
+    lexer.next()?;
+    match token {
+      Token::RParen if opened_at.is_some() => return Ok((line, None)),
+      // move numbers
+      Token::Period => {}
+      Token::Symbol(s) if s.bytes().all(|b| b.is_ascii_digit()) => {}
+      Token::Comment(text) => match line.moves.last_mut() {
+        Some((_, annotation)) => annotation.comments.push(text),
+        None => line.comments.push(text),
+      },
+      Token::Nag(nag) => match line.moves.last_mut() {
+        Some((_, annotation)) => annotation.nags.push(nag),
+        None => {
+          return Err(Lexer::error(
+            pos,
+            PgnErrorKind::UnexpectedToken(token_text(&token)),
+          ))
+        }
+      },
+      Token::LParen => {
+        // the variation replaces the last move
+        let Some(&before) = positions.len().checked_sub(2).map(|i| &positions[i]) else {
+          return Err(Lexer::error(pos, PgnErrorKind::MisplacedVariation));
+        };
+        let (variation, _) = read_line(lexer, before, Some(pos))?;
+        line.moves.last_mut().unwrap().1.variations.push(variation);
+      }
+      Token::Symbol(symbol) => {
+        // a suffix such as "!?" stuck to the move
+        let san = symbol.trim_end_matches(['!', '?']);
+        let nags = suffix_nag(&symbol[san.len()..]).into_iter().collect();
+
+        let board = positions.last().unwrap();
+        let mv = board
+          .parse_san(san)
+          .map_err(|err| Lexer::error(pos, PgnErrorKind::IllegalMove(err)))?;
+        positions.push(board.apply_move(mv));
+        line.moves.push((
+          mv,
+          Annotation {
+            nags,
+            ..Annotation::default()
+          },
+        ));
+      }
+      token => {
+        return Err(Lexer::error(
+          pos,
+          PgnErrorKind::UnexpectedToken(token_text(&token)),
+        ))
+      }
+    }
+  }
+}
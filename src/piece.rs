@@ -1,13 +1,5 @@
 use std::ops::Not;
 
-// x-offsets for displaying
-pub const PAWN_XOFF: i32 = 10;
-pub const KNIGHT_XOFF: i32 = 5;
-pub const ROOK_XOFF: i32 = 6;
-pub const BISHOP_XOFF: i32 = 3;
-pub const QUEEN_XOFF: i32 = -2;
-pub const KING_XOFF: i32 = 2;
-
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum PieceColor {
   White = 0,
@@ -58,6 +50,21 @@ pub enum PieceType {
   King = 5,
 }
 
+impl PieceType {
+  // material value in centipawns, as used by eval and SEE
+  #[inline]
+  pub const fn value(self) -> i32 {
+    match self {
+      PieceType::Pawn => 100,
+      PieceType::Knight => 320,
+      PieceType::Bishop => 330,
+      PieceType::Rook => 500,
+      PieceType::Queen => 900,
+      PieceType::King => 20_000,
+    }
+  }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Piece {
   pub class: PieceType,
@@ -83,6 +90,44 @@ impl Piece {
   pub fn is_king(self) -> bool {
     self.class == PieceType::King
   }
+
+  // FEN letter, uppercase for white and lowercase for black
+  pub fn to_char(self) -> char {
+    let c = match self.class {
+      PieceType::Pawn => 'p',
+      PieceType::Knight => 'n',
+      PieceType::Bishop => 'b',
+      PieceType::Rook => 'r',
+      PieceType::Queen => 'q',
+      PieceType::King => 'k',
+    };
+
+    if self.color.is_white() {
+      c.to_ascii_uppercase()
+    } else {
+      c
+    }
+  }
+
+  pub fn from_char(c: char) -> Option<Self> {
+    let class = match c.to_ascii_lowercase() {
+      'p' => PieceType::Pawn,
+      'n' => PieceType::Knight,
+      'b' => PieceType::Bishop,
+      'r' => PieceType::Rook,
+      'q' => PieceType::Queen,
+      'k' => PieceType::King,
+      _ => return None,
+    };
+
+    let color = if c.is_ascii_uppercase() {
+      PieceColor::White
+    } else {
+      PieceColor::Black
+    };
+
+    Some(Piece { class, color })
+  }
 }
 
 pub const WP: Piece = Piece {
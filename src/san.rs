@@ -0,0 +1,253 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::board::{Board, Move, MoveKind};
+use crate::movegen::is_in_check;
+use crate::piece::*;
+use crate::square::{parse_square, square_name};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SanError {
+  /// The text isn't a move in algebraic notation at all.
+  Syntax(String),
+  /// The move is well-formed but no legal move in the position matches it.
+  IllegalMove(String),
+  /// More than one legal move matches; `candidates` holds them in SAN.
+  AmbiguousMove {
+    san: String,
+    candidates: Vec<String>,
+  },
+}
+
+impl fmt::Display for SanError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      SanError::Syntax(san) => write!(f, "invalid SAN: '{san}'"),
+      SanError::IllegalMove(san) => write!(f, "illegal move in this position: '{san}'"),
+      SanError::AmbiguousMove { san, candidates } => write!(
+        f,
+        "ambiguous move '{san}', could be any of: {}",
+        candidates.join(", ")
+      ),
+    }
+  }
+}
+
+impl Error for SanError {}
+
+fn piece_letter(class: PieceType) -> char {
+  Piece {
+    class,
+    color: PieceColor::White,
+  }
+  .to_char()
+}
+
+fn piece_from_letter(c: char) -> Option<PieceType> {
+  Piece::from_char(c)
+    .filter(|p| p.color.is_white())
+    .map(|p| p.class)
+}
+
+impl Move {
+  /// Standard Algebraic Notation for this move, which has to be legal in
+  /// `board`: piece letter, just enough of the origin square to tell it
+  /// apart from other legal moves, `x` for captures, `=Q` for promotions and
+  /// `+` or `#` for check and mate.
+  pub fn to_san(self, board: &Board) -> String {
+    let mut san = match self.kind() {
+      MoveKind::KingsideCastle => "O-O".to_string(),
+      MoveKind::QueensideCastle => "O-O-O".to_string(),
+      _ => self.san_body(board),
+    };
+
+    let after = board.apply_move(self);
+    if is_in_check(&after, after.side_to_move()) {
+      san.push(if after.moves_for_player().is_empty() {
+        '#'
+      } else {
+        '+'
+      });
+    }
+
+    san
+  }
+
+  // everything but castling and the check suffix
+  fn san_body(self, board: &Board) -> String {
+    let class = board[self.from()]
+      .expect("move should start on an occupied square")
+      .class;
+    let (from_x, from_y) = self.from();
+
+    let mut san = String::new();
+
+    if class == PieceType::Pawn {
+      if self.is_capture() {
+        san.push((b'a' + from_x as u8) as char);
+      }
+    } else {
+      san.push(piece_letter(class));
+
+      // other pieces of the same type that can go to the same square
+      let others: Vec<(u32, u32)> = board
+        .moves_for_player()
+        .iter()
+        .filter(|mv| mv.to() == self.to() && mv.from() != self.from())
+        .filter(|mv| board[mv.from()].map(|p| p.class) == Some(class))
+        .map(|mv| mv.from())
+        .collect();
+
+      if !others.is_empty() {
+        let file = (b'a' + from_x as u8) as char;
+        let rank = char::from_digit(8 - from_y, 10).unwrap();
+
+        if others.iter().all(|&(x, _)| x != from_x) {
+          san.push(file);
+        } else if others.iter().all(|&(_, y)| y != from_y) {
+          san.push(rank);
+        } else {
+          san.push(file);
+          san.push(rank);
+        }
+      }
+    }
+
+    if self.is_capture() {
+      san.push('x');
+    }
+
+    san.push_str(&square_name(self.to()));
+
+    if let Some(class) = self.promotion() {
+      san.push('=');
+      san.push(piece_letter(class));
+    }
+
+    san
+  }
+}
+
+// what a SAN string says about the move it names; `None` means it doesn't
+// say
+struct SanParts {
+  class: PieceType,
+  from_file: Option<u32>,
+  from_rank: Option<u32>,
+  to: (u32, u32),
+  promotion: Option<PieceType>,
+}
+
+fn parse_parts(san: &str) -> Option<SanParts> {
+  let mut chars: Vec<char> = san.chars().collect();
+
+  let class = match chars.first().copied().and_then(piece_from_letter) {
+    Some(class) => {
+      chars.remove(0);
+      class
+    }
+    None => PieceType::Pawn,
+  };
+
+  // promotion piece at the end: "e8=Q", "e8Q", "e8=q" or "e8q". Squares end
+  // in a digit, so a letter right after one can only be the promotion.
+  let mut promotion = None;
+  if let [.., before, last] = chars[..] {
+    if before == '=' || before.is_ascii_digit() {
+      if let Some(promo) = Piece::from_char(last).map(|p| p.class) {
+        promotion = Some(promo);
+        chars.truncate(chars.len() - if before == '=' { 2 } else { 1 });
+      }
+    }
+  }
+
+  if chars.len() < 2 {
+    return None;
+  }
+
+  let to: String = chars[chars.len() - 2..].iter().collect();
+  let to = parse_square(&to)?;
+  chars.truncate(chars.len() - 2);
+
+  // "x" (or ":") for captures is optional
+  if matches!(chars.last(), Some('x' | ':')) {
+    chars.pop();
+  }
+
+  let (mut from_file, mut from_rank) = (None, None);
+  for c in chars {
+    match c {
+      'a'..='h' if from_file.is_none() && from_rank.is_none() => {
+        from_file = Some(c as u32 - 'a' as u32)
+      }
+      '1'..='8' if from_rank.is_none() => from_rank = Some('8' as u32 - c as u32),
+      _ => return None,
+    }
+  }
+
+  Some(SanParts {
+    class,
+    from_file,
+    from_rank,
+    to,
+    promotion,
+  })
+}
+
+impl Board {
+  /// Find the legal move named by `san`.
+  ///
+  /// Besides strict SAN this accepts the usual lenient forms: missing or
+  /// superfluous check marks, annotation glyphs (`!`, `?`), `0-0` or `o-o`
+  /// for castling, a missing `x`, a promotion without `=` or with a
+  /// lowercase letter (`e8q`), a trailing `e.p.` and over-specified origin
+  /// squares such as `Ng1f3`.
+  pub fn parse_san(&self, san: &str) -> Result<Move, SanError> {
+    let trimmed = san
+      .trim()
+      .trim_end_matches(['+', '#', '!', '?'])
+      .trim_end_matches("e.p.")
+      .trim_end();
+
+    let candidates: Vec<Move> = match trimmed.replace(['0', 'o'], "O").as_str() {
+      "O-O" => self.castling_moves(MoveKind::KingsideCastle),
+      "O-O-O" => self.castling_moves(MoveKind::QueensideCastle),
+      _ => {
+        let parts = parse_parts(trimmed).ok_or_else(|| SanError::Syntax(san.to_string()))?;
+
+        self
+          .moves_for_player()
+          .iter()
+          .copied()
+          .filter(|mv| {
+            let (x, y) = mv.from();
+            self[mv.from()].map(|p| p.class) == Some(parts.class)
+              && mv.to() == parts.to
+              && !mv.is_castle()
+              && parts.from_file.is_none_or(|file| file == x)
+              && parts.from_rank.is_none_or(|rank| rank == y)
+              && mv.promotion() == parts.promotion
+          })
+          .collect()
+      }
+    };
+
+    match candidates.as_slice() {
+      [] => Err(SanError::IllegalMove(san.to_string())),
+      [mv] => Ok(*mv),
+      _ => Err(SanError::AmbiguousMove {
+        san: san.to_string(),
+        candidates: candidates.iter().map(|mv| mv.to_san(self)).collect(),
+      }),
+    }
+  }
+
+  fn castling_moves(&self, kind: MoveKind) -> Vec<Move> {
+    self
+      .moves_for_player()
+      .iter()
+      .copied()
+      .filter(|mv| mv.kind() == kind)
+      .collect()
+  }
+}
@@ -1,24 +1,199 @@
-use crate::*;
+use std::time::{Duration, Instant};
 
-pub fn minimax(
+use crate::board::{Board, Move, MATE_SCORE};
+use crate::movegen::{has_legal_move, is_in_check};
+use crate::movelist::MoveList;
+use crate::tablebase::Tablebase;
+
+// what a search needs besides the position: when to give up, and tables to
+// look endings up in
+struct Context<'a> {
+  // once this has passed, every node returns straight away and whatever the
+  // search returns is meaningless
+  deadline: Option<Instant>,
+  passed: bool,
+  tablebase: Option<&'a Tablebase>,
+}
+
+impl Context<'_> {
+  fn check(&mut self) -> bool {
+    if !self.passed {
+      self.passed = self.deadline.is_some_and(|at| Instant::now() >= at);
+    }
+    self.passed
+  }
+}
+
+// searches for the side to move in `board`
+pub fn minimax(board: Board, depth: u32, alpha: i32, beta: i32) -> (Option<Move>, i32) {
+  minimax_with_history(board, &[], depth, alpha, beta)
+}
+
+// same as minimax, but `history` holds the positions played before `board`
+// (oldest first), so that lines repeating them are scored as draws
+pub fn minimax_with_history(
+  board: Board,
+  history: &[Board],
+  depth: u32,
+  alpha: i32,
+  beta: i32,
+) -> (Option<Move>, i32) {
+  let mut context = Context {
+    deadline: None,
+    passed: false,
+    tablebase: None,
+  };
+  search_from(board, history, &mut context, depth, alpha, beta)
+}
+
+// same as minimax_with_history, but positions `tablebase` covers are scored
+// by it instead of searched, and if it covers `board` itself its best move
+// is played straight away
+pub fn minimax_with_tablebase(
   board: Board,
+  history: &[Board],
+  depth: u32,
+  tablebase: &Tablebase,
+) -> (Option<Move>, i32) {
+  if let Some((mv, outcome)) = tablebase.best_move(&board) {
+    return (Some(mv), outcome.score(board.side_to_move()));
+  }
+
+  let mut context = Context {
+    deadline: None,
+    passed: false,
+    tablebase: Some(tablebase),
+  };
+  search_from(board, history, &mut context, depth, i32::MIN, i32::MAX)
+}
+
+fn search_from(
+  board: Board,
+  history: &[Board],
+  context: &mut Context,
+  depth: u32,
+  alpha: i32,
+  beta: i32,
+) -> (Option<Move>, i32) {
+  let mut board = board;
+  let mut path = Vec::with_capacity(history.len() + depth as usize);
+  path.extend_from_slice(history);
+  search(&mut board, &mut path, context, depth, true, alpha, beta)
+}
+
+// iterative deepening: search to depth 1, 2, ... up to `max_depth` until
+// `time` runs out, and return the result of the deepest search that finished
+// along with its depth. The depth 1 search always finishes, however little
+// time there is.
+pub fn minimax_timed(
+  board: Board,
+  history: &[Board],
+  max_depth: u32,
+  time: Duration,
+) -> (Option<Move>, i32, u32) {
+  let mut context = Context {
+    deadline: Instant::now().checked_add(time),
+    passed: false,
+    tablebase: None,
+  };
+
+  let mut board = board;
+  let mut path = Vec::with_capacity(history.len() + max_depth as usize);
+  path.extend_from_slice(history);
+
+  let (mv, value) = minimax_with_history(board, history, 1, i32::MIN, i32::MAX);
+  let mut best = (mv, value, 1);
+
+  for depth in 2..=max_depth {
+    let (mv, value) = search(
+      &mut board,
+      &mut path,
+      &mut context,
+      depth,
+      true,
+      i32::MIN,
+      i32::MAX,
+    );
+    if context.check() {
+      break;
+    }
+    best = (mv, value, depth);
+  }
+
+  best
+}
+
+// `board` is played forward with make_move and restored before returning,
+// and `path` only grows up to the search depth, so nothing is allocated
+// per node apart from the move lists
+fn search(
+  board: &mut Board,
+  path: &mut Vec<Board>,
+  context: &mut Context,
   depth: u32,
-  color: PieceColor,
+  is_root: bool,
   mut alpha: i32,
   mut beta: i32,
 ) -> (Option<Move>, i32) {
+  // the move list doubles as the checkmate and stalemate test; leaves don't
+  // need the list, only whether there is a move
+  let moves = if depth > 0 {
+    board.moves_for_player()
+  } else {
+    MoveList::default()
+  };
+  let can_move = if depth > 0 {
+    !moves.is_empty()
+  } else {
+    has_legal_move(board)
+  };
+
+  if !can_move {
+    let score = if !is_in_check(board, board.side_to_move()) {
+      0
+    } else if board.side_to_move().is_white() {
+      -MATE_SCORE
+    } else {
+      MATE_SCORE
+    };
+    return (None, score);
+  }
+
+  // don't stop at the root on a claimable draw, the caller still wants a move
+  if !is_root {
+    if board.draw_status(path).is_over() {
+      return (None, 0);
+    }
+
+    if let Some(outcome) = context.tablebase.and_then(|tb| tb.probe(board)) {
+      return (None, outcome.score(board.side_to_move()));
+    }
+  }
+
   if depth == 0 {
-    return (None, board.eval(color));
+    return (None, board.eval_pieces());
+  }
+
+  if context.check() {
+    return (None, 0);
   }
 
-  let moves = board.moves_for_player(color);
+  let color = board.side_to_move();
+
+  path.push(*board);
 
   // maximizing player
-  if color.is_white() {
+  let result = if color.is_white() {
     let mut best_val = i32::MIN;
     let mut best_move = None;
     for mv in moves {
-      let (_, value) = minimax(board.apply_move(mv), depth - 1, !color, alpha, beta);
+      let undo = board.make_move(mv);
+      let (_, value) = search(board, path, context, depth - 1, false, alpha, beta);
+      board.unmake_move(undo);
+
+      if context.passed {
+        break;
+      }
 
       if value > best_val {
         best_move = Some(mv);
@@ -31,12 +206,18 @@ pub fn minimax(
         break;
       }
     }
-    return (best_move, best_val);
+    (best_move, best_val)
   } else {
     let mut best_val = i32::MAX;
     let mut best_move = None;
     for mv in moves {
-      let (_, value) = minimax(board.apply_move(mv), depth - 1, !color, alpha, beta);
+      let undo = board.make_move(mv);
+      let (_, value) = search(board, path, context, depth - 1, false, alpha, beta);
+      board.unmake_move(undo);
+
+      if context.passed {
+        break;
+      }
 
       if value < best_val {
         best_move = Some(mv);
@@ -49,6 +230,10 @@ pub fn minimax(
         break;
       }
     }
-    return (best_move, best_val);
-  }
+    (best_move, best_val)
+  };
+
+  path.pop();
+
+  result
 }
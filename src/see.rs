@@ -0,0 +1,103 @@
+use crate::bitboard::Bitboard;
+use crate::board::{Board, Move, MoveKind};
+use crate::movegen::attackers;
+use crate::piece::*;
+
+// cheapest first, so the first class with an attacker is the one to use
+const CAPTURE_ORDER: [PieceType; 6] = [
+  PieceType::Pawn,
+  PieceType::Knight,
+  PieceType::Bishop,
+  PieceType::Rook,
+  PieceType::Queen,
+  PieceType::King,
+];
+
+impl Board {
+  /// Static exchange evaluation: material gained by the side making `mv`,
+  /// in centipawns, once every capture on the destination square has been
+  /// played out with the cheapest attacker first, each side being free to
+  /// stop capturing when that is better for it.
+  ///
+  /// Pieces joining in from behind other attackers (x-rays) are accounted
+  /// for. Pins and promotions during the exchange are not, so this is an
+  /// estimate for ordering and pruning rather than an exact result. Quiet
+  /// moves are evaluated as well: a negative score means the piece can be
+  /// taken for free (or for less than it is worth) on its new square.
+  pub fn see(&self, mv: Move) -> i32 {
+    let (from, to) = (mv.from_sq(), mv.to_sq());
+
+    let piece = match self[from] {
+      Some(piece) => piece,
+      None => return 0,
+    };
+
+    let mut occupied = self.occupied() ^ Bitboard::from_square(from);
+
+    // gain[i]: material won by the side making the i-th capture, assuming
+    // the exchange stops right after it
+    let mut gain = [0i32; 32];
+    gain[0] = match mv.kind() {
+      MoveKind::EnPassant => {
+        let captured = (to as i32 - 8 * piece.color.direction()) as u32;
+        occupied ^= Bitboard::from_square(captured);
+        PieceType::Pawn.value()
+      }
+      _ => self[to].map(|p| p.class.value()).unwrap_or(0),
+    };
+
+    // value of the piece now standing on the target square
+    let mut on_square = piece.class.value();
+    if let Some(class) = mv.promotion() {
+      gain[0] += class.value() - PieceType::Pawn.value();
+      on_square = class.value();
+    }
+
+    let all_attackers = |occupied| {
+      (attackers(self, to, PieceColor::White, occupied)
+        | attackers(self, to, PieceColor::Black, occupied))
+        & occupied
+    };
+
+    let mut remaining = all_attackers(occupied);
+    let mut side = !piece.color;
+    let mut depth = 0;
+
+    while depth + 1 < gain.len() {
+      let ours = remaining & self.color_pieces(side);
+
+      let Some((class, sq)) = CAPTURE_ORDER.iter().find_map(|&class| {
+        (ours & self.pieces(class, side))
+          .first()
+          .map(|sq| (class, sq))
+      }) else {
+        break;
+      };
+
+      let after = occupied ^ Bitboard::from_square(sq);
+
+      // the king can only recapture if nothing defends the square anymore
+      if class == PieceType::King && !(all_attackers(after) & self.color_pieces(!side)).is_empty() {
+        break;
+      }
+
+      depth += 1;
+      gain[depth] = on_square - gain[depth - 1];
+
+      on_square = class.value();
+      occupied = after;
+      // sliders behind the piece that just captured can now see the square
+      remaining = all_attackers(occupied);
+      side = !side;
+    }
+
+    // each side only makes a capture if it doesn't leave it worse off than
+    // stopping before it
+    while depth > 0 {
+      gain[depth - 1] = -i32::max(-gain[depth - 1], gain[depth]);
+      depth -= 1;
+    }
+
+    gain[0]
+  }
+}
@@ -0,0 +1,108 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// A square on the board, stored as its index `8 * y + x` (so a8 is 0 and
+/// h1 is 63, y = 0 being rank 8). Converts to and from the `(x, y)` pairs
+/// used throughout the rest of the crate, and to and from algebraic names
+/// like `e4`, which is where getting the inverted y-axis wrong is easiest.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Square(u32);
+
+impl Square {
+  /// `None` if the index is off the board.
+  #[inline]
+  pub const fn from_index(idx: u32) -> Option<Self> {
+    if idx < 64 {
+      Some(Square(idx))
+    } else {
+      None
+    }
+  }
+
+  /// `None` if the coordinates are off the board.
+  #[inline]
+  pub const fn from_coord((x, y): (u32, u32)) -> Option<Self> {
+    if x < 8 && y < 8 {
+      Some(Square(8 * y + x))
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  pub const fn index(self) -> u32 {
+    self.0
+  }
+
+  #[inline]
+  pub const fn coord(self) -> (u32, u32) {
+    (self.0 % 8, self.0 / 8)
+  }
+
+  /// File letter, `'a'..='h'`.
+  #[inline]
+  pub const fn file_char(self) -> char {
+    (b'a' + (self.0 % 8) as u8) as char
+  }
+
+  /// Rank digit, `'1'..='8'`.
+  #[inline]
+  pub const fn rank_char(self) -> char {
+    (b'8' - (self.0 / 8) as u8) as char
+  }
+}
+
+impl fmt::Display for Square {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}{}", self.file_char(), self.rank_char())
+  }
+}
+
+impl fmt::Debug for Square {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "Square({self})")
+  }
+}
+
+/// The string wasn't a square name like `e4`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseSquareError(pub String);
+
+impl fmt::Display for ParseSquareError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "invalid square: '{}'", self.0)
+  }
+}
+
+impl Error for ParseSquareError {}
+
+impl FromStr for Square {
+  type Err = ParseSquareError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.as_bytes() {
+      &[file @ b'a'..=b'h', rank @ b'1'..=b'8'] => {
+        Ok(Square(8 * (b'8' - rank) as u32 + (file - b'a') as u32))
+      }
+      _ => Err(ParseSquareError(s.to_string())),
+    }
+  }
+}
+
+impl From<Square> for (u32, u32) {
+  fn from(sq: Square) -> Self {
+    sq.coord()
+  }
+}
+
+// algebraic square name -> (x, y), where y = 0 is rank 8
+pub(crate) fn parse_square(s: &str) -> Option<(u32, u32)> {
+  s.parse().ok().map(Square::coord)
+}
+
+pub(crate) fn square_name((x, y): (u32, u32)) -> String {
+  debug_assert!(x < 8 && y < 8);
+
+  Square(8 * y + x).to_string()
+}
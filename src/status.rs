@@ -0,0 +1,185 @@
+use std::fmt;
+
+use crate::board::Board;
+use crate::movegen::{has_legal_move, is_in_check};
+use crate::piece::*;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GameResult {
+  WhiteWins,
+  BlackWins,
+  Draw,
+}
+
+impl GameResult {
+  pub fn win_for(color: PieceColor) -> Self {
+    if color.is_white() {
+      GameResult::WhiteWins
+    } else {
+      GameResult::BlackWins
+    }
+  }
+}
+
+/// State of the game in a position, taking the game history into account
+/// for repetitions.
+///
+/// The fifty-move rule and threefold repetition only entitle a player to
+/// claim a draw; the seventy-five-move rule and fivefold repetition end the
+/// game automatically. Both kinds are reported, so the front end can decide
+/// whether to adjudicate the claimable ones.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GameStatus {
+  Ongoing,
+  Checkmate {
+    winner: PieceColor,
+  },
+  Stalemate,
+  /// Neither side can possibly checkmate.
+  InsufficientMaterial,
+  SeventyFiveMoveRule,
+  FivefoldRepetition,
+  FiftyMoveRule,
+  ThreefoldRepetition,
+}
+
+impl GameStatus {
+  /// `None` while the game is still going.
+  pub fn result(self) -> Option<GameResult> {
+    match self {
+      GameStatus::Ongoing => None,
+      GameStatus::Checkmate { winner } => Some(GameResult::win_for(winner)),
+      _ => Some(GameResult::Draw),
+    }
+  }
+
+  pub fn is_over(self) -> bool {
+    self != GameStatus::Ongoing
+  }
+
+  /// Whether this is a draw that has to be claimed by a player rather than
+  /// one that ends the game by itself.
+  pub fn is_claimable(self) -> bool {
+    matches!(
+      self,
+      GameStatus::FiftyMoveRule | GameStatus::ThreefoldRepetition
+    )
+  }
+}
+
+impl fmt::Display for GameStatus {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      GameStatus::Ongoing => f.write_str("game in progress"),
+      GameStatus::Checkmate { winner } => {
+        let winner = if winner.is_white() { "White" } else { "Black" };
+        write!(f, "checkmate, {winner} wins")
+      }
+      GameStatus::Stalemate => f.write_str("draw by stalemate"),
+      GameStatus::InsufficientMaterial => f.write_str("draw by insufficient material"),
+      GameStatus::SeventyFiveMoveRule => f.write_str("draw by the seventy-five-move rule"),
+      GameStatus::FivefoldRepetition => f.write_str("draw by fivefold repetition"),
+      GameStatus::FiftyMoveRule => f.write_str("draw by the fifty-move rule"),
+      GameStatus::ThreefoldRepetition => f.write_str("draw by threefold repetition"),
+    }
+  }
+}
+
+impl Board {
+  /// Status of the game in this position. `history` holds the positions
+  /// that came before this one, oldest first (not including this one).
+  pub fn status(&self, history: &[Board]) -> GameStatus {
+    // checkmate takes precedence over the move-count rules (FIDE 9.6.2)
+    if !has_legal_move(self) {
+      return if is_in_check(self, self.side_to_move) {
+        GameStatus::Checkmate {
+          winner: !self.side_to_move,
+        }
+      } else {
+        GameStatus::Stalemate
+      };
+    }
+
+    self.draw_status(history)
+  }
+
+  // the status of a position the side to move has a move in, which can
+  // only be ongoing or one of the draws
+  pub(crate) fn draw_status(&self, history: &[Board]) -> GameStatus {
+    if self.is_insufficient_material() {
+      return GameStatus::InsufficientMaterial;
+    }
+
+    let repetitions = self.repetition_count(history);
+
+    if self.halfmove_clock >= 150 {
+      GameStatus::SeventyFiveMoveRule
+    } else if repetitions >= 5 {
+      GameStatus::FivefoldRepetition
+    } else if self.halfmove_clock >= 100 {
+      GameStatus::FiftyMoveRule
+    } else if repetitions >= 3 {
+      GameStatus::ThreefoldRepetition
+    } else {
+      GameStatus::Ongoing
+    }
+  }
+
+  /// Whether two boards are the same position for the purposes of
+  /// repetition: same pieces on the same squares, same side to move, same
+  /// castling rights and the same en passant capture available, if any (see
+  /// [`Board::en_passant_capture_square`]). The move counters are ignored.
+  pub fn same_position(&self, other: &Board) -> bool {
+    // the key rules out almost every mismatch without comparing the boards
+    self.hash == other.hash
+      && self.board == other.board
+      && self.side_to_move == other.side_to_move
+      && self.castling_rights == other.castling_rights
+      && self.en_passant_capture_square() == other.en_passant_capture_square()
+  }
+
+  /// How many times this position has occurred, counting this occurrence.
+  pub fn repetition_count(&self, history: &[Board]) -> usize {
+    // a capture or pawn move can never be undone, so only the positions since
+    // the last one can repeat; of those, only every other one has the same
+    // side to move
+    let reversible = (self.halfmove_clock as usize).min(history.len());
+
+    1 + history[history.len() - reversible..]
+      .iter()
+      .rev()
+      .skip(1)
+      .step_by(2)
+      .filter(|b| self.same_position(b))
+      .count()
+  }
+
+  /// Neither side has enough material left to ever deliver checkmate: king
+  /// against king, king and minor piece against king, or only bishops left
+  /// that all stand on squares of the same color.
+  pub fn is_insufficient_material(&self) -> bool {
+    let mut knights = 0;
+    let mut bishop_square_colors = [false; 2];
+
+    for (idx, square) in self.board.iter().enumerate() {
+      match square.map(|p| p.class) {
+        None | Some(PieceType::King) => {}
+        Some(PieceType::Knight) => knights += 1,
+        Some(PieceType::Bishop) => {
+          let (x, y) = (idx % 8, idx / 8);
+          bishop_square_colors[(x + y) % 2] = true;
+        }
+        // pawns can promote, and rooks and queens can mate on their own
+        Some(_) => return false,
+      }
+    }
+
+    match (knights, bishop_square_colors) {
+      // bare kings, or bishops all on one color
+      (0, [light, dark]) => !(light && dark),
+      // one knight and nothing else
+      (1, [false, false]) => true,
+      _ => false,
+    }
+  }
+}
@@ -0,0 +1,738 @@
+//! Distance-to-mate endgame tables, generated by retrograde analysis.
+//!
+//! Tables cover endings of up to [`MAX_PIECES`] pieces. Castling never
+//! matters in them, and neither does en passant as long as only one side
+//! has pawns, so the tables leave both out; positions with castling rights
+//! or an en passant capture available aren't probed. Endings with pawns on
+//! both sides (KPKP) are turned down instead of generated: a double push
+//! there can be answered en passant, which the tables can't represent, so
+//! the position before the push could get the wrong value. The fifty-move
+//! rule is ignored too: a won position is won however long the mate takes.
+//!
+//! These are the crate's own tables, not Syzygy ones: `.rtbw` and `.rtbz`
+//! files are not read, and [`Tablebase::load_dir`] skips them like any
+//! other file that isn't a `.tb` table.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::attacks::piece_attacks;
+use crate::bitboard::Bitboard;
+use crate::board::{Board, Move, MATE_SCORE};
+use crate::movegen::{is_in_check, legal_moves};
+use crate::piece::*;
+
+/// Largest number of pieces, kings included, a table can cover.
+pub const MAX_PIECES: usize = 4;
+
+/// First bytes of every table file.
+pub const TABLE_MAGIC: &[u8; 8] = b"CHESSTB1";
+
+// table values: 0 is a draw, 1..=254 is mate in (value - 1) plies, won by
+// the side to move if that's odd and lost if it's even
+const DRAW: u8 = 0;
+const ILLEGAL: u8 = 255;
+
+/// The theoretical result of a position for the side to move, with the
+/// distance to mate in plies.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+  Win(u32),
+  Draw,
+  /// `Loss(0)` means checkmated.
+  Loss(u32),
+}
+
+impl Outcome {
+  fn from_value(value: u8) -> Option<Self> {
+    match value {
+      ILLEGAL => None,
+      DRAW => Some(Outcome::Draw),
+      v if (v - 1) % 2 == 1 => Some(Outcome::Win(v as u32 - 1)),
+      v => Some(Outcome::Loss(v as u32 - 1)),
+    }
+  }
+
+  /// The outcome for the other side, one ply earlier: what this position
+  /// means for the player who moves into it.
+  pub fn before(self) -> Self {
+    match self {
+      Outcome::Win(plies) => Outcome::Loss(plies + 1),
+      Outcome::Draw => Outcome::Draw,
+      Outcome::Loss(plies) => Outcome::Win(plies + 1),
+    }
+  }
+
+  /// Search score from White's point of view, `side_to_move` being the side
+  /// the outcome is for. Quicker mates score higher.
+  pub fn score(self, side_to_move: PieceColor) -> i32 {
+    let score = match self {
+      Outcome::Win(plies) => MATE_SCORE - plies as i32,
+      Outcome::Draw => 0,
+      Outcome::Loss(plies) => -MATE_SCORE + plies as i32,
+    };
+    if side_to_move.is_white() {
+      score
+    } else {
+      -score
+    }
+  }
+
+  // higher is better for the side to move
+  fn rank(self) -> i64 {
+    match self {
+      Outcome::Win(plies) => i64::MAX - plies as i64,
+      Outcome::Draw => 0,
+      Outcome::Loss(plies) => i64::MIN + plies as i64,
+    }
+  }
+}
+
+#[derive(Debug)]
+pub enum TablebaseError {
+  Io(io::Error),
+  /// Not a material signature like `KQK` or `KBNK`.
+  InvalidMaterial(String),
+  /// The material has more than [`MAX_PIECES`] pieces.
+  TooManyPieces(String),
+  /// Both sides have pawns, so en passant could matter.
+  PawnsOnBothSides(String),
+  /// A table file that doesn't hold a table.
+  InvalidFile(String),
+}
+
+impl fmt::Display for TablebaseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      TablebaseError::Io(err) => err.fmt(f),
+      TablebaseError::InvalidMaterial(name) => write!(f, "invalid material: '{name}'"),
+      TablebaseError::TooManyPieces(name) => {
+        write!(f, "{name} has more than {MAX_PIECES} pieces")
+      }
+      TablebaseError::PawnsOnBothSides(name) => {
+        write!(
+          f,
+          "{name} has pawns on both sides, which en passant rules out"
+        )
+      }
+      TablebaseError::InvalidFile(name) => write!(f, "invalid table file: {name}"),
+    }
+  }
+}
+
+impl Error for TablebaseError {}
+
+impl From<io::Error> for TablebaseError {
+  fn from(err: io::Error) -> Self {
+    TablebaseError::Io(err)
+  }
+}
+
+fn letter(class: PieceType) -> char {
+  Piece {
+    class,
+    color: PieceColor::White,
+  }
+  .to_char()
+}
+
+// The pieces of an ending besides the kings, strongest first, for the
+// stronger side and the weaker side. Tables are built with the stronger
+// side as White; positions where it's Black are looked up color-flipped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Material {
+  strong: Vec<PieceType>,
+  weak: Vec<PieceType>,
+}
+
+impl Material {
+  // the material with `a` and `b`, and whether `b` turned out stronger
+  fn new(mut a: Vec<PieceType>, mut b: Vec<PieceType>) -> (Material, bool) {
+    let strength = |pieces: &[PieceType]| {
+      let values: Vec<i32> = pieces.iter().map(|p| p.value()).collect();
+      (values.iter().sum::<i32>(), values)
+    };
+
+    a.sort_by_key(|p| -p.value());
+    b.sort_by_key(|p| -p.value());
+
+    if strength(&b) > strength(&a) {
+      (Material { strong: b, weak: a }, true)
+    } else {
+      (Material { strong: a, weak: b }, false)
+    }
+  }
+
+  // "KQK", "KBNK", "KRKN", "KQvK"...
+  fn parse(name: &str) -> Result<Material, TablebaseError> {
+    let invalid = || TablebaseError::InvalidMaterial(name.to_string());
+
+    let upper = name.to_ascii_uppercase().replace('V', "");
+    let rest = upper.strip_prefix('K').ok_or_else(invalid)?;
+    let (a, b) = rest.split_once('K').ok_or_else(invalid)?;
+
+    let pieces = |s: &str| {
+      s.chars()
+        .map(|c| match c {
+          'Q' => Some(PieceType::Queen),
+          'R' => Some(PieceType::Rook),
+          'B' => Some(PieceType::Bishop),
+          'N' => Some(PieceType::Knight),
+          'P' => Some(PieceType::Pawn),
+          _ => None,
+        })
+        .collect::<Option<Vec<_>>>()
+    };
+
+    let (material, _) = Material::new(
+      pieces(a).ok_or_else(invalid)?,
+      pieces(b).ok_or_else(invalid)?,
+    );
+    if material.len() > MAX_PIECES {
+      return Err(TablebaseError::TooManyPieces(material.name()));
+    }
+    if material.strong.contains(&PieceType::Pawn) && material.weak.contains(&PieceType::Pawn) {
+      return Err(TablebaseError::PawnsOnBothSides(material.name()));
+    }
+    Ok(material)
+  }
+
+  fn name(&self) -> String {
+    let mut name = String::from("K");
+    name.extend(self.strong.iter().map(|&p| letter(p)));
+    name.push('K');
+    name.extend(self.weak.iter().map(|&p| letter(p)));
+    name
+  }
+
+  // the material on `board`, and whether Black is the stronger side
+  fn of_board(board: &Board) -> (Material, bool) {
+    let pieces = |color| {
+      [
+        PieceType::Queen,
+        PieceType::Rook,
+        PieceType::Bishop,
+        PieceType::Knight,
+        PieceType::Pawn,
+      ]
+      .into_iter()
+      .flat_map(|class| (0..board.pieces(class, color).count()).map(move |_| class))
+      .collect()
+    };
+    Material::new(pieces(PieceColor::White), pieces(PieceColor::Black))
+  }
+
+  fn len(&self) -> usize {
+    2 + self.strong.len() + self.weak.len()
+  }
+
+  fn has_pawns(&self) -> bool {
+    self.strong.contains(&PieceType::Pawn) || self.weak.contains(&PieceType::Pawn)
+  }
+
+  // no sequence of legal moves can mate, whatever the squares
+  fn is_dead_draw(&self) -> bool {
+    let all = || self.strong.iter().chain(&self.weak);
+    all().all(|&p| matches!(p, PieceType::Knight | PieceType::Bishop)) && all().count() <= 1
+  }
+
+  // squares the stronger king is mirrored into: a1-d4 without pawns,
+  // files a-d with them
+  fn king_squares(&self) -> usize {
+    if self.has_pawns() {
+      32
+    } else {
+      16
+    }
+  }
+
+  fn table_len(&self) -> usize {
+    self.king_squares() * 64usize.pow(self.len() as u32 - 1) * 2
+  }
+
+  // kind of piece at each position in a table's square list: the stronger
+  // king and pieces (White), then the weaker ones (Black)
+  fn pieces(&self) -> Vec<Piece> {
+    let white = |class| Piece {
+      class,
+      color: PieceColor::White,
+    };
+    let black = |class| Piece {
+      class,
+      color: PieceColor::Black,
+    };
+
+    let mut pieces = vec![white(PieceType::King)];
+    pieces.extend(self.strong.iter().map(|&p| white(p)));
+    pieces.push(black(PieceType::King));
+    pieces.extend(self.weak.iter().map(|&p| black(p)));
+    pieces
+  }
+
+  // endings one capture and/or promotion away
+  fn successors(&self) -> Vec<Material> {
+    let mut out = Vec::new();
+
+    for (mover, other) in [(&self.strong, &self.weak), (&self.weak, &self.strong)] {
+      // None for a move that captures nothing
+      let captures = (0..other.len()).map(Some).chain([None]);
+      for captured in captures {
+        let mut other = other.clone();
+        if let Some(i) = captured {
+          other.remove(i);
+        }
+
+        if captured.is_some() {
+          out.push(Material::new(mover.clone(), other.clone()).0);
+        }
+        for (i, _) in mover
+          .iter()
+          .enumerate()
+          .filter(|&(_, &p)| p == PieceType::Pawn)
+        {
+          for promo in [
+            PieceType::Knight,
+            PieceType::Bishop,
+            PieceType::Rook,
+            PieceType::Queen,
+          ] {
+            let mut mover = mover.clone();
+            mover[i] = promo;
+            out.push(Material::new(mover, other.clone()).0);
+          }
+        }
+      }
+    }
+
+    let mut unique = Vec::new();
+    for material in out {
+      if !unique.contains(&material) {
+        unique.push(material);
+      }
+    }
+    unique
+  }
+
+  // Table index of a position, `squares` being in `pieces()` order as
+  // `8 * y + x`. The stronger king is mirrored into its region first.
+  fn index(&self, squares: &[u32], strong_to_move: bool) -> usize {
+    let king = squares[0];
+    let flip_x = king % 8 > 3;
+    // without pawns the board can be flipped top to bottom too
+    let flip_y = !self.has_pawns() && king / 8 < 4;
+
+    let mirror = |sq: u32| {
+      let (x, y) = (sq % 8, sq / 8);
+      let x = if flip_x { 7 - x } else { x };
+      let y = if flip_y { 7 - y } else { y };
+      8 * y + x
+    };
+
+    let king = mirror(king);
+    let (x, y) = (king % 8, king / 8);
+    let mut index = if self.has_pawns() {
+      4 * y + x
+    } else {
+      4 * (y - 4) + x
+    } as usize;
+
+    for &sq in &squares[1..] {
+      index = index * 64 + mirror(sq) as usize;
+    }
+    index * 2 + !strong_to_move as usize
+  }
+
+  // inverse of `index`, into `squares`
+  fn decode(&self, mut index: usize, squares: &mut [u32]) -> bool {
+    let strong_to_move = index.is_multiple_of(2);
+    index /= 2;
+
+    for sq in squares[1..].iter_mut().rev() {
+      *sq = (index % 64) as u32;
+      index /= 64;
+    }
+
+    let (x, y) = (index as u32 % 4, index as u32 / 4);
+    squares[0] = if self.has_pawns() {
+      8 * y + x
+    } else {
+      8 * (y + 4) + x
+    };
+
+    strong_to_move
+  }
+}
+
+#[derive(Clone, Debug)]
+struct Table {
+  material: Material,
+  data: Vec<u8>,
+}
+
+impl Table {
+  // the file format: TABLE_MAGIC, the name's length and bytes, the number
+  // of entries, then runs of equal values as (length, value), all
+  // big-endian
+  fn to_bytes(&self) -> Vec<u8> {
+    let name = self.material.name();
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(TABLE_MAGIC);
+    bytes.push(name.len() as u8);
+    bytes.extend_from_slice(name.as_bytes());
+    bytes.extend_from_slice(&(self.data.len() as u32).to_be_bytes());
+
+    for run in self.data.chunk_by(|a, b| a == b) {
+      for part in run.chunks(u16::MAX as usize) {
+        bytes.extend_from_slice(&(part.len() as u16).to_be_bytes());
+        bytes.push(part[0]);
+      }
+    }
+    bytes
+  }
+
+  fn from_bytes(bytes: &[u8]) -> Option<Table> {
+    let rest = bytes.strip_prefix(TABLE_MAGIC)?;
+    let (&name_len, rest) = rest.split_first()?;
+    let name = std::str::from_utf8(rest.get(..name_len as usize)?).ok()?;
+    let material = Material::parse(name).ok()?;
+    let rest = &rest[name_len as usize..];
+    let len = u32::from_be_bytes(rest.get(..4)?.try_into().ok()?) as usize;
+    if len != material.table_len() {
+      return None;
+    }
+
+    let mut data = Vec::with_capacity(len);
+    for run in rest[4..].chunks(3) {
+      let &[hi, lo, value] = run else {
+        return None;
+      };
+      data.extend(std::iter::repeat_n(
+        value,
+        u16::from_be_bytes([hi, lo]) as usize,
+      ));
+    }
+
+    (data.len() == len).then_some(Table { material, data })
+  }
+}
+
+/// Distance-to-mate tables for endings of up to [`MAX_PIECES`] pieces; see
+/// the module docs for what they leave out.
+#[derive(Clone, Debug, Default)]
+pub struct Tablebase {
+  tables: HashMap<String, Table>,
+}
+
+impl Tablebase {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Names of the tables held, such as `KQK`.
+  pub fn tables(&self) -> Vec<String> {
+    let mut names: Vec<String> = self.tables.keys().cloned().collect();
+    names.sort();
+    names
+  }
+
+  /// The largest number of pieces any table covers.
+  pub fn max_pieces(&self) -> usize {
+    self
+      .tables
+      .values()
+      .map(|t| t.material.len())
+      .max()
+      .unwrap_or(0)
+  }
+
+  /// Outcome of `board` for the side to move, if a table covers it.
+  pub fn probe(&self, board: &Board) -> Option<Outcome> {
+    if board.occupied().count() as usize > MAX_PIECES
+      || board.castling_rights.iter().flatten().any(|&right| right)
+      || board.en_passant_capture_square().is_some()
+    {
+      return None;
+    }
+
+    if board.is_insufficient_material() {
+      return Some(Outcome::Draw);
+    }
+
+    let (material, black_is_strong) = Material::of_board(board);
+    let table = self.tables.get(&material.name())?;
+
+    // squares in table order, color-flipped if Black is the stronger side
+    let mut squares = [0; MAX_PIECES];
+    let mut taken = Bitboard::EMPTY;
+    for (piece, sq) in material.pieces().into_iter().zip(&mut squares) {
+      let color = if black_is_strong {
+        !piece.color
+      } else {
+        piece.color
+      };
+      // identical pieces are interchangeable, so take them in any order
+      let found = (board.pieces(piece.class, color) & !taken).first()?;
+      taken |= Bitboard::from_square(found);
+      *sq = if black_is_strong { found ^ 56 } else { found };
+    }
+
+    let strong_to_move = board.side_to_move().is_white() != black_is_strong;
+    let index = material.index(&squares[..material.len()], strong_to_move);
+    Outcome::from_value(table.data[index])
+  }
+
+  /// The best move in `board` by the tables, and the outcome it leads to
+  /// for the side to move: the quickest win, any draw, or failing that the
+  /// longest loss. `None` if the position or a move out of it isn't
+  /// covered.
+  pub fn best_move(&self, board: &Board) -> Option<(Move, Outcome)> {
+    let mut best: Option<(Move, Outcome)> = None;
+
+    for mv in board.moves_for_player() {
+      let outcome = self.probe(&board.apply_move(mv))?.before();
+      if best.is_none_or(|(_, b)| outcome.rank() > b.rank()) {
+        best = Some((mv, outcome));
+      }
+    }
+
+    best
+  }
+
+  /// Generate the table for `material` (like `KQK` or `KBNK`), and first
+  /// those for any endings a capture or promotion leads to, unless they're
+  /// already held.
+  pub fn generate(&mut self, material: &str) -> Result<(), TablebaseError> {
+    let material = Material::parse(material)?;
+    self.generate_material(&material);
+    Ok(())
+  }
+
+  fn generate_material(&mut self, material: &Material) {
+    if material.is_dead_draw() || self.tables.contains_key(&material.name()) {
+      return;
+    }
+
+    for next in material.successors() {
+      self.generate_material(&next);
+    }
+
+    let table = self.build(material);
+    self.tables.insert(material.name(), table);
+  }
+
+  // retrograde analysis for one ending
+  fn build(&self, material: &Material) -> Table {
+    let len = material.table_len();
+    let n = material.len();
+    let pieces = material.pieces();
+
+    let mut value = vec![DRAW; len];
+    // moves that stay in this table and haven't been found to lose yet
+    let mut moves_left = vec![0u8; len];
+    // 0, or 1 + the longest loss among moves leaving the table
+    // (captures and promotions), or ILLEGAL if one of them wins or draws
+    let mut exit_loss = vec![0u8; len];
+    // positions to settle at each ply, won on odd plies and lost on even
+    let mut pending: Vec<Vec<u32>> = Vec::new();
+    let push = |pending: &mut Vec<Vec<u32>>, ply: usize, index: usize| {
+      assert!(ply < ILLEGAL as usize - 1, "mate too long to store");
+      if pending.len() <= ply {
+        pending.resize_with(ply + 1, Vec::new);
+      }
+      pending[ply].push(index as u32);
+    };
+
+    let mut squares = [0u32; MAX_PIECES];
+    for index in 0..len {
+      let strong_to_move = material.decode(index, &mut squares[..n]);
+      let Some(board) = setup(&pieces, &squares[..n], strong_to_move) else {
+        value[index] = ILLEGAL;
+        continue;
+      };
+
+      let moves = legal_moves(&board);
+      if moves.is_empty() {
+        if is_in_check(&board, board.side_to_move()) {
+          push(&mut pending, 0, index);
+        }
+        continue;
+      }
+
+      for mv in moves {
+        if !mv.is_capture() && mv.promotion().is_none() {
+          moves_left[index] += 1;
+          continue;
+        }
+
+        let outcome = self
+          .probe(&board.apply_move(mv))
+          .expect("tables for captures and promotions come first");
+
+        match outcome.before() {
+          Outcome::Win(ply) => {
+            push(&mut pending, ply as usize, index);
+            exit_loss[index] = ILLEGAL;
+          }
+          Outcome::Draw => exit_loss[index] = ILLEGAL,
+          Outcome::Loss(ply) => {
+            if exit_loss[index] != ILLEGAL {
+              exit_loss[index] = exit_loss[index].max(ply as u8 + 1);
+            }
+          }
+        }
+      }
+
+      // every move leaves the table, and they all lose
+      if moves_left[index] == 0 && exit_loss[index] != ILLEGAL {
+        push(&mut pending, exit_loss[index] as usize - 1, index);
+      }
+    }
+
+    let mut ply = 0;
+    while ply < pending.len() {
+      for index in std::mem::take(&mut pending[ply]) {
+        let index = index as usize;
+        if value[index] != DRAW {
+          continue;
+        }
+        value[index] = ply as u8 + 1;
+
+        let strong_to_move = material.decode(index, &mut squares[..n]);
+        for_each_unmove(&pieces, &mut squares[..n], strong_to_move, |squares| {
+          let prev = material.index(squares, !strong_to_move);
+          if value[prev] != DRAW {
+            return;
+          }
+
+          if ply % 2 == 0 {
+            // moving here wins
+            push(&mut pending, ply + 1, prev);
+          } else {
+            moves_left[prev] -= 1;
+            if moves_left[prev] == 0 && exit_loss[prev] != ILLEGAL {
+              // lost, as slowly as the longest of its losing moves
+              let loss = (ply + 1).max((exit_loss[prev] as usize).saturating_sub(1));
+              push(&mut pending, loss, prev);
+            }
+          }
+        });
+      }
+      ply += 1;
+    }
+
+    Table {
+      material: material.clone(),
+      data: value,
+    }
+  }
+
+  /// Write every table to `dir`, one `NAME.tb` file each.
+  pub fn save_dir(&self, dir: impl AsRef<Path>) -> io::Result<()> {
+    fs::create_dir_all(&dir)?;
+    for (name, table) in &self.tables {
+      fs::write(dir.as_ref().join(format!("{name}.tb")), table.to_bytes())?;
+    }
+    Ok(())
+  }
+
+  /// Read every `.tb` file in `dir`.
+  pub fn load_dir(dir: impl AsRef<Path>) -> Result<Tablebase, TablebaseError> {
+    let mut tablebase = Tablebase::new();
+    for entry in fs::read_dir(dir)? {
+      let path = entry?.path();
+      if path.extension().is_some_and(|ext| ext == "tb") {
+        let table = Table::from_bytes(&fs::read(&path)?)
+          .ok_or_else(|| TablebaseError::InvalidFile(path.display().to_string()))?;
+        tablebase.tables.insert(table.material.name(), table);
+      }
+    }
+    Ok(tablebase)
+  }
+}
+
+// The position with `pieces` on `squares`, if it's a legal one: no two
+// pieces on a square, no pawns on the first or last rank, and the side that
+// just moved not in check.
+fn setup(pieces: &[Piece], squares: &[u32], strong_to_move: bool) -> Option<Board> {
+  let mut board = [None; 64];
+  for (&piece, &sq) in pieces.iter().zip(squares) {
+    let rank_y = sq / 8;
+    if board[sq as usize].is_some()
+      || (piece.class == PieceType::Pawn && (rank_y == 0 || rank_y == 7))
+    {
+      return None;
+    }
+    board[sq as usize] = Some(piece);
+  }
+
+  let mut board = Board::with_pieces(board);
+  board.side_to_move = if strong_to_move {
+    PieceColor::White
+  } else {
+    PieceColor::Black
+  };
+  board.hash = board.compute_hash();
+
+  (!is_in_check(&board, !board.side_to_move)).then_some(board)
+}
+
+// Call `f` with the squares of every position one non-capturing,
+// non-promoting move before this one, which the side not to move made.
+fn for_each_unmove(
+  pieces: &[Piece],
+  squares: &mut [u32],
+  strong_to_move: bool,
+  mut f: impl FnMut(&[u32]),
+) {
+  let mover = if strong_to_move {
+    PieceColor::Black
+  } else {
+    PieceColor::White
+  };
+  let occupied: Bitboard = squares.iter().copied().collect();
+
+  for i in 0..pieces.len() {
+    let Piece { class, color } = pieces[i];
+    if color != mover {
+      continue;
+    }
+
+    let sq = squares[i];
+    let origins = if class == PieceType::Pawn {
+      // White pawns move up the board, towards y = 0
+      let back = |steps: u32| {
+        let y = sq / 8;
+        let from_y = if color.is_white() {
+          y + steps
+        } else {
+          y.wrapping_sub(steps)
+        };
+        (from_y < 8).then(|| 8 * from_y + sq % 8)
+      };
+      let double_rank = if color.is_white() { 4 } else { 3 };
+
+      let mut origins = Bitboard::EMPTY;
+      if let Some(one) = back(1).filter(|&s| !occupied.contains(s)) {
+        origins |= Bitboard::from_square(one);
+        if sq / 8 == double_rank {
+          if let Some(two) = back(2).filter(|&s| !occupied.contains(s)) {
+            origins |= Bitboard::from_square(two);
+          }
+        }
+      }
+      origins
+    } else {
+      piece_attacks(class, sq, occupied) & !occupied
+    };
+
+    for from in origins {
+      squares[i] = from;
+      f(squares);
+    }
+    squares[i] = sq;
+  }
+}
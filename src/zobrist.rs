@@ -0,0 +1,72 @@
+//! Random keys for Zobrist hashing. A position's key is the XOR of the key
+//! of every (piece, square) pair on the board, the side-to-move key when
+//! black is to move, one key per castling right still held and the key of
+//! the en passant file if an en passant capture is legal. Each of those
+//! changes by XORing a single key in or out, so `Board` can keep its key up
+//! to date while making moves.
+//!
+//! The keys are generated at compile time from a fixed seed, so hashes are
+//! stable between runs (but are unrelated to the Polyglot book keys).
+
+use crate::piece::{Piece, PieceColor};
+
+const PIECE_KEYS_LEN: usize = 2 * 6 * 64;
+const SIDE_KEY_IDX: usize = PIECE_KEYS_LEN;
+const CASTLING_KEYS_IDX: usize = SIDE_KEY_IDX + 1;
+const EN_PASSANT_KEYS_IDX: usize = CASTLING_KEYS_IDX + 4;
+const NUM_KEYS: usize = EN_PASSANT_KEYS_IDX + 8;
+
+// splitmix64, which is good enough for hash keys and easy to run in a const
+const fn generate_keys() -> [u64; NUM_KEYS] {
+  let mut keys = [0; NUM_KEYS];
+  let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+
+  let mut i = 0;
+  while i < NUM_KEYS {
+    state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    keys[i] = z ^ (z >> 31);
+    i += 1;
+  }
+
+  keys
+}
+
+static KEYS: [u64; NUM_KEYS] = generate_keys();
+
+#[inline(always)]
+pub(crate) fn piece_key(piece: Piece, sq: u32) -> u64 {
+  KEYS[(piece.color as usize * 6 + piece.class as usize) * 64 + sq as usize]
+}
+
+#[inline(always)]
+pub(crate) fn side_key(side: PieceColor) -> u64 {
+  if side.is_black() {
+    KEYS[SIDE_KEY_IDX]
+  } else {
+    0
+  }
+}
+
+#[inline(always)]
+pub(crate) fn castling_key(rights: [[bool; 2]; 2]) -> u64 {
+  let mut key = 0;
+  for color in 0..2 {
+    for side in 0..2 {
+      if rights[color][side] {
+        key ^= KEYS[CASTLING_KEYS_IDX + 2 * color + side];
+      }
+    }
+  }
+  key
+}
+
+#[inline(always)]
+pub(crate) fn en_passant_key(square: Option<(u32, u32)>) -> u64 {
+  match square {
+    Some((x, _)) => KEYS[EN_PASSANT_KEYS_IDX + x as usize],
+    None => 0,
+  }
+}
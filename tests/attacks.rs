@@ -0,0 +1,41 @@
+use chess::{Bitboard, Board, PieceColor};
+
+const WHITE: PieceColor = PieceColor::White;
+const BLACK: PieceColor = PieceColor::Black;
+
+fn squares(bb: Bitboard) -> Vec<(u32, u32)> {
+  bb.squares().map(|sq| (sq % 8, sq / 8)).collect()
+}
+
+#[test]
+fn attackers_of_a_square() {
+  // e5 is attacked by the d4 pawn, the c6 knight (black), the e1 rook (white)
+  // and the h8 bishop (black); the a1 queen is blocked by d4
+  let board = Board::from_fen("4k2b/8/2n5/4p3/3P4/8/8/Q3RK2 w - - 0 1").unwrap();
+  let e5 = (4, 3);
+
+  assert_eq!(squares(board.attackers_to(e5, WHITE)), vec![(3, 4), (4, 7)]);
+  assert_eq!(squares(board.attackers_to(e5, BLACK)), vec![(7, 0), (2, 2)]);
+  assert!(board.is_attacked(e5, WHITE));
+  assert!(!board.is_attacked((0, 4), BLACK));
+
+  // the queen stands behind the d4 pawn on the a1-h8 diagonal
+  assert_eq!(squares(board.xray_attackers_to(e5, WHITE)), vec![(0, 7)]);
+  assert!(board.xray_attackers_to(e5, BLACK).is_empty());
+}
+
+#[test]
+fn xray_attackers_stack() {
+  // white rooks and a queen stacked on the d-file against the d5 pawn, which
+  // the d8 rook defends
+  let board = Board::from_fen("3r2k1/8/8/3p4/3R4/3R4/3Q4/6K1 w - - 0 1").unwrap();
+  let d5 = (3, 3);
+
+  assert_eq!(squares(board.attackers_to(d5, WHITE)), vec![(3, 4)]);
+  assert_eq!(
+    squares(board.xray_attackers_to(d5, WHITE)),
+    vec![(3, 5), (3, 6)]
+  );
+  assert_eq!(squares(board.attackers_to(d5, BLACK)), vec![(3, 0)]);
+  assert!(board.xray_attackers_to(d5, BLACK).is_empty());
+}
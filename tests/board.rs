@@ -0,0 +1,288 @@
+mod common;
+
+use chess::{Board, Move, MoveKind, Piece, PieceColor, PieceType};
+use common::play;
+
+#[test]
+fn apply_move_updates_side_and_counters() {
+  let board = Board::new();
+  assert_eq!(board.side_to_move(), PieceColor::White);
+
+  // 1. Nf3
+  let board = play(&board, (6, 7), (5, 5));
+  assert_eq!(board.side_to_move(), PieceColor::Black);
+  assert_eq!((board.halfmove_clock(), board.fullmove_number()), (1, 1));
+
+  // 1... Nf6
+  let board = play(&board, (6, 0), (5, 2));
+  assert_eq!(board.side_to_move(), PieceColor::White);
+  assert_eq!((board.halfmove_clock(), board.fullmove_number()), (2, 2));
+
+  // 2. e4 resets the halfmove clock
+  let board = play(&board, (4, 6), (4, 4));
+  assert_eq!((board.halfmove_clock(), board.fullmove_number()), (0, 2));
+
+  // 2... Nxe4 (capture) also resets it
+  let board = play(&board, (5, 2), (4, 4));
+  assert_eq!(
+    board.to_fen(),
+    "rnbqkb1r/pppppppp/8/8/4n3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 0 3"
+  );
+}
+
+#[test]
+fn moves_are_for_side_to_move() {
+  let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 b Q - 0 1").unwrap();
+  let moves = board.moves_for_player();
+
+  assert_eq!(moves.len(), 5);
+  assert!(moves
+    .iter()
+    .all(|mv| board[mv.from()] == board[(4u32, 0u32)]));
+}
+
+#[test]
+fn promotions_include_underpromotion_and_captures() {
+  use chess::search::minimax;
+  use chess::PieceType::*;
+
+  // a7 pawn can push to a8 or capture on b8
+  let board = Board::from_fen("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+  let promotions: Vec<Move> = board
+    .moves_for_player()
+    .into_iter()
+    .filter(|mv| mv.promotion().is_some())
+    .collect();
+
+  assert_eq!(promotions.len(), 8);
+  for to in [(0, 0), (1, 0)] {
+    for class in [Knight, Bishop, Rook, Queen] {
+      assert!(promotions
+        .iter()
+        .any(|mv| mv.to() == to && mv.promotion() == Some(class)));
+    }
+  }
+
+  // search sees the promotion moves too
+  let (best, _) = minimax(board, 1, i32::MIN, i32::MAX);
+  let best = best.unwrap();
+  assert_eq!(
+    (best.to(), best.kind()),
+    ((1, 0), MoveKind::PromotionCapture(Queen))
+  );
+}
+
+#[test]
+fn hash_identifies_positions() {
+  let start = Board::new();
+  assert_eq!(
+    start.hash(),
+    Board::from_fen(chess::STARTING_FEN).unwrap().hash()
+  );
+
+  // 1. Nf3 Nf6 2. Nc3 and 1. Nc3 Nf6 2. Nf3 transpose
+  let a = play(
+    &play(&play(&start, (6, 7), (5, 5)), (6, 0), (5, 2)),
+    (1, 7),
+    (2, 5),
+  );
+  let b = play(
+    &play(&play(&start, (1, 7), (2, 5)), (6, 0), (5, 2)),
+    (6, 7),
+    (5, 5),
+  );
+  assert_eq!(a.hash(), b.hash());
+
+  // side to move, castling rights and the en passant square all count
+  let fens = [
+    "4k3/8/8/3pP3/8/8/8/R3K2R w KQ d6 0 1",
+    "4k3/8/8/3pP3/8/8/8/R3K2R w KQ - 0 1",
+    "4k3/8/8/3pP3/8/8/8/R3K2R w K - 0 1",
+    "4k3/8/8/3pP3/8/8/8/R3K2R b KQ - 0 1",
+  ];
+  let hashes: Vec<u64> = fens
+    .iter()
+    .map(|fen| Board::from_fen(fen).unwrap().hash())
+    .collect();
+  for i in 0..hashes.len() {
+    for j in i + 1..hashes.len() {
+      assert_ne!(hashes[i], hashes[j], "{} / {}", fens[i], fens[j]);
+    }
+  }
+
+  // the move counters don't
+  assert_eq!(
+    hashes[1],
+    Board::from_fen("4k3/8/8/3pP3/8/8/8/R3K2R w KQ - 12 40")
+      .unwrap()
+      .hash()
+  );
+  // nor does an en passant square no pawn can take on, as for repetitions
+  let hash = |fen: &str| Board::from_fen(fen).unwrap().hash();
+  assert_eq!(
+    hash("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"),
+    hash("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1")
+  );
+  // or only a pinned one
+  assert_eq!(
+    hash("8/8/8/8/k2pP2R/8/8/4K3 b - e3 0 1"),
+    hash("8/8/8/8/k2pP2R/8/8/4K3 b - - 0 1")
+  );
+
+  // set_piece keeps the key in step, taking away or bringing back the only
+  // pawn that can capture en passant
+  let mut board = Board::from_fen(fens[0]).unwrap();
+  board.set_piece((4, 3), None);
+  assert_eq!(board.hash(), hash(&board.to_fen()));
+  assert_eq!(board.hash(), hash("4k3/8/8/3p4/8/8/8/R3K2R w KQ - 0 1"));
+  board.set_piece(
+    (2, 3),
+    Some(Piece {
+      class: PieceType::Pawn,
+      color: PieceColor::White,
+    }),
+  );
+  assert_eq!(board.hash(), hash(&board.to_fen()));
+  assert_ne!(board.hash(), hash("4k3/8/8/2Pp4/8/8/8/R3K2R w KQ - 0 1"));
+  // and doesn't mind the king being lifted off, as the GUI does for drawing
+  board.set_piece((4, 7), None);
+  board.set_piece(
+    (4, 7),
+    Some(Piece {
+      class: PieceType::King,
+      color: PieceColor::White,
+    }),
+  );
+  assert_eq!(board.hash(), hash(&board.to_fen()));
+}
+
+// makes every move two plies deep and checks that unmaking restores the
+// position exactly, and that make_move agrees with apply_move
+fn check_make_unmake(board: &mut Board, depth: u32) {
+  if depth == 0 {
+    return;
+  }
+
+  let fen = board.to_fen();
+  let hash = board.hash();
+  for mv in board.moves_for_player() {
+    let expected = board.apply_move(mv).to_fen();
+
+    let undo = board.make_move(mv);
+    assert_eq!(board.to_fen(), expected);
+    // the incrementally updated key matches one computed from scratch
+    assert_eq!(board.hash(), Board::from_fen(&expected).unwrap().hash());
+    check_make_unmake(board, depth - 1);
+    board.unmake_move(undo);
+
+    assert_eq!(board.to_fen(), fen, "after unmaking {mv:?}");
+    assert_eq!(board.hash(), hash);
+  }
+}
+
+#[test]
+fn unmake_move_restores_position() {
+  for fen in [
+    // castling both ways, en passant and rook captures
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    // en passant available right away
+    "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+    // promotions, including capturing promotions onto a rook's home square
+    "r3k2r/1P4P1/8/8/8/8/1p4p1/R3K2R w KQkq - 5 40",
+  ] {
+    let mut board = Board::from_fen(fen).unwrap();
+    check_make_unmake(&mut board, 2);
+    assert_eq!(board.to_fen(), fen);
+  }
+}
+
+#[test]
+fn move_kinds_match_reference_counts() {
+  // kiwipete at depth 2: 2039 nodes, of which 351 captures (1 en passant)
+  // and 91 castles, per the chessprogramming wiki perft results
+  let board =
+    Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+      .unwrap();
+
+  let (mut nodes, mut captures, mut en_passant, mut castles) = (0, 0, 0, 0);
+  for mv in board.moves_for_player() {
+    for reply in board.apply_move(mv).moves_for_player() {
+      nodes += 1;
+      captures += reply.is_capture() as u32;
+      en_passant += (reply.kind() == MoveKind::EnPassant) as u32;
+      castles += reply.is_castle() as u32;
+    }
+  }
+
+  assert_eq!((nodes, captures, en_passant, castles), (2039, 351, 1, 91));
+
+  // the packed encoding round-trips every kind
+  for kind in [
+    MoveKind::Quiet,
+    MoveKind::DoublePawnPush,
+    MoveKind::KingsideCastle,
+    MoveKind::QueensideCastle,
+    MoveKind::Capture,
+    MoveKind::EnPassant,
+    MoveKind::Promotion(chess::PieceType::Knight),
+    MoveKind::PromotionCapture(chess::PieceType::Queen),
+  ] {
+    let mv = Move::new(12, 63, kind);
+    assert_eq!((mv.from_sq(), mv.to_sq(), mv.kind()), (12, 63, kind));
+  }
+}
+
+#[test]
+fn legal_moves_respect_checks_and_pins() {
+  use chess::{legal_moves_for_piece, moves_for_piece};
+
+  let targets = |fen: &str| -> Vec<(u32, u32)> {
+    let board = Board::from_fen(fen).unwrap();
+    let mut to: Vec<_> = board.moves_for_player().iter().map(|mv| mv.to()).collect();
+    to.sort();
+    to
+  };
+
+  // double check from the rook and the knight: only the king moves, and not
+  // along the rook's file
+  let board = Board::from_fen("4r1k1/8/8/8/8/3n4/8/R3K3 w Q - 0 1").unwrap();
+  assert!(board
+    .moves_for_player()
+    .iter()
+    .all(|mv| mv.from() == (4, 7) && mv.to().0 != 4));
+  assert_eq!(targets("4r1k1/8/8/8/8/3n4/8/R3K3 w Q - 0 1").len(), 3);
+
+  // single check: the knight can only block on c3 or d2, not go to a3
+  let board = Board::from_fen("4k3/8/8/8/1q6/8/8/1N2K3 w - - 0 1").unwrap();
+  let mut knight: Vec<_> = legal_moves_for_piece(&board, (1, 7))
+    .iter()
+    .map(|mv| mv.to())
+    .collect();
+  knight.sort();
+  assert_eq!(knight, vec![(2, 5), (3, 6)]);
+
+  // pinned rook slides along the pin but can't leave it
+  let board = Board::from_fen("4k3/4r3/8/8/8/8/4R3/4K3 w - - 0 1").unwrap();
+  let rook = legal_moves_for_piece(&board, (4, 6));
+  assert_eq!(rook.len(), 5);
+  assert!(rook.iter().all(|mv| mv.to().0 == 4));
+  assert_eq!(moves_for_piece(&board, (4, 6)).len(), 12);
+
+  // en passant would take both pawns off the fifth rank and expose the king
+  // to the rook
+  let board = Board::from_fen("8/8/8/KPp4r/8/8/8/4k3 w - c6 0 2").unwrap();
+  assert!(board
+    .moves_for_player()
+    .iter()
+    .all(|mv| mv.kind() != MoveKind::EnPassant));
+  assert!(moves_for_piece(&board, (1, 3))
+    .iter()
+    .any(|mv| mv.kind() == MoveKind::EnPassant));
+
+  // but capturing the checking pawn en passant is fine
+  let board = Board::from_fen("8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1").unwrap();
+  assert!(board
+    .moves_for_player()
+    .iter()
+    .any(|mv| mv.kind() == MoveKind::EnPassant));
+}
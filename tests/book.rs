@@ -0,0 +1,128 @@
+use chess::book::BOOK_MAGIC;
+use chess::{parse_pgn, Board, Book, BookBuilder, BookError};
+
+const GAMES: &str = "
+[Result \"1-0\"]
+1. e4 e5 2. Nf3 Nc6 1-0
+
+[Result \"0-1\"]
+1. e4 c5 2. Nf3 0-1
+
+[Result \"1/2-1/2\"]
+1. d4 e6 2. e4 d5 1/2-1/2
+
+1. e4 e6 2. d4 d5 *
+";
+
+fn build_book(max_ply: usize) -> Book {
+  let mut builder = BookBuilder::new(max_ply);
+  for game in parse_pgn(GAMES).unwrap() {
+    builder.add_game(&game);
+  }
+  assert_eq!(builder.games(), 4);
+  builder.build()
+}
+
+fn after(moves: &str) -> Board {
+  let mut board = Board::new();
+  for san in moves.split_whitespace() {
+    board = board.apply_move(board.parse_san(san).unwrap());
+  }
+  board
+}
+
+fn sans(book: &Book, moves: &str) -> Vec<(String, u16)> {
+  let board = after(moves);
+  book
+    .moves(&board)
+    .into_iter()
+    .map(|(mv, weight)| (mv.to_san(&board), weight))
+    .collect()
+}
+
+fn weights(list: &[(&str, u16)]) -> Vec<(String, u16)> {
+  list.iter().map(|&(san, w)| (san.to_string(), w)).collect()
+}
+
+#[test]
+fn weights_follow_results() {
+  let book = build_book(20);
+
+  // 2 for a win, 1 for a draw or unfinished game, 0 for a loss
+  assert_eq!(sans(&book, ""), weights(&[("e4", 3), ("d4", 1)]));
+  assert_eq!(sans(&book, "e4"), weights(&[("c5", 2), ("e6", 1)]));
+  // the only move played here lost
+  assert!(sans(&book, "e4 e5 Nf3").is_empty());
+
+  // both move orders reach the same position, despite the en passant
+  // squares left behind by different double pushes
+  assert_eq!(sans(&book, "d4 e6 e4"), weights(&[("d5", 2)]));
+  assert_eq!(sans(&book, "e4 e6 d4"), weights(&[("d5", 2)]));
+
+  let short = build_book(1);
+  assert_eq!(sans(&short, ""), weights(&[("e4", 3), ("d4", 1)]));
+  assert!(sans(&short, "e4").is_empty());
+}
+
+#[test]
+fn files_round_trip() {
+  let book = build_book(20);
+  let bytes = book.to_bytes();
+  assert!(bytes.starts_with(BOOK_MAGIC));
+  assert_eq!(bytes.len(), 8 + 16 * book.len());
+
+  let read = Book::from_bytes(&bytes).unwrap();
+  assert_eq!(read.to_bytes(), bytes);
+
+  assert!(matches!(
+    Book::from_bytes(b"not a book"),
+    Err(BookError::NotABook)
+  ));
+  // books keyed by an older scheme are refused rather than silently missed
+  let mut old = bytes.clone();
+  old[7] = b'1';
+  assert!(matches!(
+    Book::from_bytes(&old),
+    Err(BookError::UnsupportedVersion(b'1'))
+  ));
+
+  // the entries are Polyglot's, so the same book reads as a Polyglot one
+  let polyglot = Book::from_polyglot_bytes(&bytes[8..]).unwrap();
+  assert_eq!(sans(&polyglot, "e4"), sans(&book, "e4"));
+
+  assert!(matches!(
+    Book::from_bytes(&bytes[..bytes.len() - 1]),
+    Err(BookError::Truncated)
+  ));
+  assert!(matches!(
+    Book::load("/nonexistent/book.bin"),
+    Err(BookError::Io(_))
+  ));
+}
+
+#[test]
+fn picks_by_weight() {
+  let book = build_book(20);
+  let board = Board::new();
+
+  // e4 covers 0..3 and d4 3..4 of the total weight of 4
+  let picks: Vec<String> = (0..8)
+    .map(|r| book.pick(&board, r).unwrap().to_san(&board))
+    .collect();
+  assert_eq!(picks, ["e4", "e4", "e4", "d4", "e4", "e4", "e4", "d4"]);
+
+  assert_eq!(book.pick(&after("a3"), 0), None);
+  assert_eq!(Book::default().pick(&board, 0), None);
+}
+
+#[test]
+fn resignation_after_repetition_counts_as_a_loss() {
+  let pgn = "[Result \"1-0\"]\n\n1. Nf3 Nf6 2. Ng1 Ng8 3. Nf3 Nf6 4. Ng1 Ng8 1-0\n";
+  let mut builder = BookBuilder::new(20);
+  builder.add_game(&parse_pgn(pgn).unwrap()[0]);
+  let book = builder.build();
+
+  // Nf3 was played twice by the winner, Nf6 only by the loser
+  assert_eq!(sans(&book, ""), weights(&[("Nf3", 4)]));
+  assert!(sans(&book, "Nf3").is_empty());
+}
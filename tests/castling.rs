@@ -0,0 +1,120 @@
+mod common;
+
+use chess::{can_castle, is_move_legal, Board, Move, MoveKind, PieceColor};
+use common::play;
+
+const WHITE: PieceColor = PieceColor::White;
+const BLACK: PieceColor = PieceColor::Black;
+
+// e1 -> g1 / c1, and e8 -> g8 / c8
+const WHITE_SHORT: Move = Move::from_coords((4, 7), (6, 7), MoveKind::KingsideCastle);
+const WHITE_LONG: Move = Move::from_coords((4, 7), (2, 7), MoveKind::QueensideCastle);
+const BLACK_SHORT: Move = Move::from_coords((4, 0), (6, 0), MoveKind::KingsideCastle);
+const BLACK_LONG: Move = Move::from_coords((4, 0), (2, 0), MoveKind::QueensideCastle);
+
+fn castles(board: &Board) -> Vec<Move> {
+  board
+    .moves_for_player()
+    .into_iter()
+    .filter(|mv| mv.is_castle())
+    .collect()
+}
+
+fn assert_castling(fen: &str, short: bool, long: bool) {
+  let board = Board::from_fen(fen).unwrap();
+  let color = board.side_to_move();
+
+  assert_eq!(can_castle(&board, color, true), short, "{fen}");
+  assert_eq!(can_castle(&board, color, false), long, "{fen}");
+  assert_eq!(
+    castles(&board).len(),
+    short as usize + long as usize,
+    "{fen}"
+  );
+
+  let (short_mv, long_mv) = if color.is_white() {
+    (WHITE_SHORT, WHITE_LONG)
+  } else {
+    (BLACK_SHORT, BLACK_LONG)
+  };
+  assert_eq!(is_move_legal(&board, short_mv), short, "{fen}");
+  assert_eq!(is_move_legal(&board, long_mv), long, "{fen}");
+}
+
+#[test]
+fn both_sides_available() {
+  assert_castling("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", true, true);
+  assert_castling("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1", true, true);
+}
+
+#[test]
+fn not_out_of_check() {
+  // rook on e-file gives check
+  assert_castling("4k3/8/8/8/4r3/8/8/R3K2R w KQ - 0 1", false, false);
+  // knight check
+  assert_castling("4k3/8/8/8/8/3n4/8/R3K2R w KQ - 0 1", false, false);
+}
+
+#[test]
+fn not_through_check() {
+  // f1 attacked by a bishop
+  assert_castling("4k3/8/8/8/8/8/6b1/R3K2R w KQ - 0 1", false, true);
+  // d1 attacked by a rook
+  assert_castling("3rk3/8/8/8/8/8/8/R3K2R w KQ - 0 1", true, false);
+  // f1 attacked by a pawn
+  assert_castling("4k3/8/8/8/8/8/6p1/R3K2R w KQ - 0 1", false, true);
+  // f8 attacked by the white king is still an attacked square
+  assert_castling("r3k2r/6K1/8/8/8/8/8/8 b kq - 0 1", false, true);
+}
+
+#[test]
+fn not_into_check() {
+  // g1 attacked
+  assert_castling("4k1r1/8/8/8/8/8/8/R3K2R w KQ - 0 1", false, true);
+  // c1 attacked
+  assert_castling("2r1k3/8/8/8/8/8/8/R3K2R w KQ - 0 1", true, false);
+}
+
+#[test]
+fn path_must_be_empty() {
+  assert_castling("4k3/8/8/8/8/8/8/RN2K1NR w KQ - 0 1", false, false);
+  // b1 only needs to be empty, it may be attacked
+  assert_castling("1r2k3/8/8/8/8/8/8/R3K2R w KQ - 0 1", true, true);
+  assert_castling("4k3/8/8/8/8/8/8/R2QKB1R w KQ - 0 1", false, false);
+}
+
+#[test]
+fn rights_lost_when_rook_captured_at_home() {
+  // 1. Bxh8 captures the rook before it ever moves
+  let board = Board::from_fen("r3k2r/6B1/8/8/8/8/8/4K3 w kq - 0 1").unwrap();
+  let board = play(&board, (6, 1), (7, 0));
+  assert!(board.to_fen().contains(" b q "), "{}", board.to_fen());
+
+  // even if another rook recaptures on h8, black can't castle short
+  let board = Board::from_fen("r3k2r/6B1/7r/8/8/8/8/4K3 w kq - 0 1").unwrap();
+  let board = play(&board, (6, 1), (7, 0));
+  let board = play(&board, (7, 2), (7, 0));
+  let board = play(&board, (4, 7), (3, 7));
+  assert!(!can_castle(&board, BLACK, true));
+  assert!(can_castle(&board, BLACK, false));
+  assert!(!is_move_legal(&board, BLACK_SHORT));
+}
+
+#[test]
+fn rights_lost_when_king_or_rook_moves() {
+  let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+  // Rh1-h2, then back
+  let board = play(&board, (7, 7), (7, 6));
+  let board = play(&board, (0, 0), (0, 1));
+  let board = play(&board, (7, 6), (7, 7));
+  assert!(!can_castle(&board, WHITE, true));
+  assert!(can_castle(&board, WHITE, false));
+  assert!(!can_castle(&board, BLACK, false));
+  assert!(can_castle(&board, BLACK, true));
+
+  // Ke8-e7
+  let board = play(&board, (4, 0), (4, 1));
+  assert!(!can_castle(&board, BLACK, true));
+  assert!(!can_castle(&board, BLACK, false));
+}
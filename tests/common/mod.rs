@@ -0,0 +1,10 @@
+use chess::{moves_for_piece, Board};
+
+// the generated move from `from` to `to` (the first one, for promotions)
+pub fn play(board: &Board, from: (u32, u32), to: (u32, u32)) -> Board {
+  let mv = moves_for_piece(board, from)
+    .into_iter()
+    .find(|mv| mv.to() == to)
+    .unwrap_or_else(|| panic!("no move from {from:?} to {to:?}"));
+  board.apply_move(mv)
+}
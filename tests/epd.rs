@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use chess::search::minimax_timed;
+use chess::{Epd, EpdError, FenError, FenField, SanError};
+
+const WAC_001: &str =
+  "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id \"WAC.001\";";
+
+#[test]
+fn operations() {
+  let epd: Epd = WAC_001.parse().unwrap();
+  assert_eq!(epd.id.as_deref(), Some("WAC.001"));
+  assert_eq!(epd.best_moves.len(), 1);
+  assert_eq!(epd.best_moves[0].to_san(&epd.board), "Qg6");
+  assert!(epd.avoid_moves.is_empty());
+  assert_eq!(epd.board.halfmove_clock(), 0);
+
+  let epd: Epd = "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5Q2/PPPP1PPP/RNB1KBNR w KQkq - \
+                  am Qxf7+ Qf3xf7; c0 \"no; really\"; hmvc 3; fmvn 4; pv Nc3 Nf6;"
+    .parse()
+    .unwrap();
+  assert_eq!(epd.comment.as_deref(), Some("no; really"));
+  assert_eq!(epd.avoid_moves.len(), 2);
+  assert_eq!(epd.avoid_moves[0], epd.avoid_moves[1]);
+  assert_eq!(epd.board.halfmove_clock(), 3);
+  assert_eq!(epd.board.fullmove_number(), 4);
+  assert_eq!(
+    epd.operation("pv"),
+    Some(&["Nc3".to_string(), "Nf6".to_string()][..])
+  );
+  assert_eq!(epd.operations.len(), 5);
+
+  // without bm any move not to avoid is fine
+  let qxf7 = epd.avoid_moves[0];
+  assert!(!epd.is_solved_by(qxf7));
+  let nc3 = epd.board.parse_san("Nc3").unwrap();
+  assert!(epd.is_solved_by(nc3));
+}
+
+#[test]
+fn errors() {
+  let parse = |line: &str| line.parse::<Epd>().unwrap_err();
+
+  assert_eq!(
+    parse("8/8/8/8/8/8/8/8"),
+    EpdError::InvalidFen(FenError::MissingField(FenField::SideToMove))
+  );
+  assert_eq!(
+    parse("4k3/8/8/8/8/8/8/4K3 w - - bm Kd2"),
+    EpdError::Syntax("bm Kd2".to_string())
+  );
+  assert_eq!(
+    parse("4k3/8/8/8/8/8/8/4K3 w - - id \"open;"),
+    EpdError::Syntax("\"open;".to_string())
+  );
+  assert_eq!(
+    parse("4k3/8/8/8/8/8/8/4K3 w - - bm Ke3;"),
+    EpdError::IllegalMove {
+      opcode: "bm".to_string(),
+      error: SanError::IllegalMove("Ke3".to_string()),
+    }
+  );
+  assert_eq!(
+    parse("4k3/8/8/8/8/8/8/4K3 w - - hmvc x;").to_string(),
+    "invalid operand for hmvc: 'x'"
+  );
+}
+
+#[test]
+fn solved_under_time_limit() {
+  let epd: Epd = WAC_001.parse().unwrap();
+
+  // the first iteration always finishes
+  let (mv, _, depth) = minimax_timed(epd.board, &[], 64, Duration::ZERO);
+  assert!(mv.is_some());
+  assert_eq!(depth, 1);
+
+  let (mv, _, depth) = minimax_timed(epd.board, &[], 3, Duration::from_secs(60));
+  assert_eq!(depth, 3);
+  assert!(epd.is_solved_by(mv.unwrap()));
+}
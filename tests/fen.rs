@@ -0,0 +1,119 @@
+use chess::{Board, FenError, FenField, STARTING_FEN};
+
+#[test]
+fn starting_position_matches_new() {
+  assert_eq!(Board::new().to_fen(), STARTING_FEN);
+  assert_eq!(
+    Board::from_fen(STARTING_FEN).unwrap().to_fen(),
+    STARTING_FEN
+  );
+}
+
+#[test]
+fn round_trips_all_fields() {
+  for fen in [
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+    "rnbqkbnr/pppp1ppp/8/8/3Pp3/8/PPP1PPPP/RNBQKBNR b Kq d3 0 3",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 12 47",
+    "4k3/8/8/8/8/8/8/4K2R b K - 99 120",
+  ] {
+    assert_eq!(Board::from_fen(fen).unwrap().to_fen(), fen);
+  }
+}
+
+#[test]
+fn clocks_default_when_omitted() {
+  let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 b - -").unwrap();
+  assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/4K3 b - - 0 1");
+}
+
+#[test]
+fn reports_failing_field() {
+  let field = |fen: &str| Board::from_fen(fen).unwrap_err().field();
+
+  assert_eq!(field(""), Some(FenField::Placement));
+  assert_eq!(field("4k3/8/8/8/8/8/8/4K3 w"), Some(FenField::Castling));
+  assert_eq!(
+    field("4k3/8/8/8/8/8/8/4K4 w - - 0 1"),
+    Some(FenField::Placement)
+  );
+  assert_eq!(
+    field("4k3/8/8/8/8/8/8/8 w - - 0 1"),
+    Some(FenField::Placement)
+  );
+  assert_eq!(
+    field("4k3/8/8/8/8/8/8/4X3 w - - 0 1"),
+    Some(FenField::Placement)
+  );
+  assert_eq!(
+    field("4k3/8/8/8/8/8/8/4K3 x - - 0 1"),
+    Some(FenField::SideToMove)
+  );
+  assert_eq!(
+    field("4k3/8/8/8/8/8/8/4K3 w KK - 0 1"),
+    Some(FenField::Castling)
+  );
+  assert_eq!(
+    field("4k3/8/8/8/8/8/8/4K3 w K - 0 1"),
+    Some(FenField::Castling)
+  );
+  assert_eq!(
+    field("4k3/8/8/8/8/8/8/4K3 w - e4 0 1"),
+    Some(FenField::EnPassant)
+  );
+  assert_eq!(
+    field("4k3/8/8/8/8/8/8/4K3 w - - x 1"),
+    Some(FenField::HalfmoveClock)
+  );
+  assert_eq!(
+    field("4k3/8/8/8/8/8/8/4K3 w - - 0 0"),
+    Some(FenField::FullmoveNumber)
+  );
+
+  assert_eq!(
+    Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1 extra").unwrap_err(),
+    FenError::TrailingInput("extra".to_string())
+  );
+}
+
+#[test]
+fn rejects_impossible_positions() {
+  let field = |fen: &str| match Board::from_fen(fen) {
+    Err(FenError::InvalidField { field, .. }) => Some(field),
+    _ => None,
+  };
+
+  // no black pawn in front of the en passant square
+  assert_eq!(
+    field("4k3/8/8/8/4p3/8/8/4K3 b - d3 0 1"),
+    Some(FenField::EnPassant)
+  );
+  // or a piece on the square the pawn passed over or came from
+  assert_eq!(
+    field("4k3/8/8/8/3Pp3/3N4/8/4K3 b - d3 0 1"),
+    Some(FenField::EnPassant)
+  );
+  assert_eq!(
+    field("4k3/8/8/8/3Pp3/8/3N4/4K3 b - d3 0 1"),
+    Some(FenField::EnPassant)
+  );
+  assert!(Board::from_fen("4k3/8/8/8/3Pp3/8/8/4K3 b - d3 0 1").is_ok());
+
+  // pawns on the back ranks
+  assert_eq!(
+    field("P3k3/8/8/8/8/8/8/4K3 w - - 0 1"),
+    Some(FenField::Placement)
+  );
+  assert_eq!(
+    field("4k3/8/8/8/8/8/8/4K2p b - - 0 1"),
+    Some(FenField::Placement)
+  );
+
+  // the side that just moved is in check
+  assert_eq!(
+    field("4k3/4R3/8/8/8/8/8/4K3 w - - 0 1"),
+    Some(FenField::SideToMove)
+  );
+  assert!(Board::from_fen("4k3/4R3/8/8/8/8/8/4K3 b - - 0 1").is_ok());
+}
@@ -0,0 +1,86 @@
+use chess::PieceType::*;
+use chess::{Board, IllegalMoveError, Move, MoveKind};
+
+fn try_move(
+  fen: &str,
+  from: (u32, u32),
+  to: (u32, u32),
+  kind: MoveKind,
+) -> Result<String, IllegalMoveError> {
+  Board::from_fen(fen)
+    .unwrap()
+    .try_apply(Move::from_coords(from, to, kind))
+    .map(|board| board.to_fen())
+}
+
+#[test]
+fn legal_moves_are_applied() {
+  use MoveKind::Quiet;
+
+  // 1. e4, given without the double push flag
+  assert_eq!(
+    try_move(chess::STARTING_FEN, (4, 6), (4, 4), Quiet).unwrap(),
+    "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+  );
+
+  // castling and en passant are recognised from the squares alone
+  assert_eq!(
+    try_move("4k3/8/8/8/8/8/8/4K2R w K - 0 1", (4, 7), (6, 7), Quiet).unwrap(),
+    "4k3/8/8/8/8/8/8/5RK1 b - - 1 1"
+  );
+  assert_eq!(
+    try_move("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2", (4, 3), (3, 2), Quiet).unwrap(),
+    "4k3/8/3P4/8/8/8/8/4K3 b - - 0 2"
+  );
+}
+
+#[test]
+fn each_reason_is_reported() {
+  use IllegalMoveError::*;
+  use MoveKind::Quiet;
+
+  let start = chess::STARTING_FEN;
+
+  assert_eq!(
+    try_move(start, (4, 4), (4, 3), Quiet),
+    Err(NoPiece { square: (4, 4) })
+  );
+  assert_eq!(
+    try_move(start, (4, 1), (4, 3), Quiet),
+    Err(WrongColor { square: (4, 1) })
+  );
+  // knight moving like a bishop, pawn moving sideways without capturing
+  assert_eq!(try_move(start, (6, 7), (4, 5), Quiet), Err(InvalidMovement));
+  assert_eq!(try_move(start, (4, 6), (3, 5), Quiet), Err(InvalidMovement));
+  // bishop behind its own pawn, rook onto its own knight, pawn into a pawn
+  assert_eq!(try_move(start, (2, 7), (4, 5), Quiet), Err(Blocked));
+  assert_eq!(try_move(start, (0, 7), (1, 7), Quiet), Err(Blocked));
+  assert_eq!(
+    try_move("4k3/8/8/8/8/4p3/4P3/4K3 w - - 0 1", (4, 6), (4, 5), Quiet),
+    Err(Blocked)
+  );
+
+  // f1 is attacked, so the king can't pass through it
+  assert_eq!(
+    try_move("4kr2/8/8/8/8/8/8/4K2R w K - 0 1", (4, 7), (6, 7), Quiet),
+    Err(CastlingNotAllowed)
+  );
+  // pinned knight
+  assert_eq!(
+    try_move("4k3/4r3/8/8/8/8/4N3/4K3 w - - 0 1", (4, 6), (2, 5), Quiet),
+    Err(LeavesKingInCheck)
+  );
+
+  // a pawn reaching the last rank needs a promotion piece, and other moves
+  // can't have one
+  let promo = "8/P3k3/8/8/8/8/8/4K3 w - - 0 1";
+  assert_eq!(
+    try_move(promo, (0, 1), (0, 0), Quiet),
+    Err(InvalidPromotion)
+  );
+  assert!(try_move(promo, (0, 1), (0, 0), MoveKind::Promotion(Knight)).is_ok());
+  assert_eq!(
+    try_move(promo, (4, 7), (4, 6), MoveKind::Promotion(Queen)),
+    Err(InvalidPromotion)
+  );
+}
@@ -0,0 +1,126 @@
+// Reference node counts from https://www.chessprogramming.org/Perft_Results
+// and the edge-case collection by Peter Ellis Jones.
+
+use chess::{divide, perft, Board, STARTING_FEN};
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+const POSITION_6: &str = "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+
+fn check(fen: &str, expected: &[u64]) {
+  let board = Board::from_fen(fen).unwrap();
+  for (depth, &nodes) in (1..).zip(expected) {
+    assert_eq!(perft(&board, depth), nodes, "{fen} at depth {depth}");
+  }
+}
+
+fn check_depth(fen: &str, depth: u32, nodes: u64) {
+  let board = Board::from_fen(fen).unwrap();
+  assert_eq!(perft(&board, depth), nodes, "{fen} at depth {depth}");
+}
+
+#[test]
+fn initial_position() {
+  check(STARTING_FEN, &[20, 400, 8902]);
+}
+
+#[test]
+fn kiwipete() {
+  check(KIWIPETE, &[48, 2039, 97862]);
+}
+
+#[test]
+fn position_3() {
+  check(POSITION_3, &[14, 191, 2812, 43238]);
+}
+
+#[test]
+fn position_4() {
+  check(POSITION_4, &[6, 264, 9467]);
+}
+
+#[test]
+fn position_4_mirrored() {
+  check(
+    "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1",
+    &[6, 264, 9467],
+  );
+}
+
+#[test]
+fn position_5() {
+  check(POSITION_5, &[44, 1486, 62379]);
+}
+
+#[test]
+fn position_6() {
+  check(POSITION_6, &[46, 2079, 89890]);
+}
+
+#[test]
+fn divide_sums_to_perft() {
+  let board = Board::from_fen(KIWIPETE).unwrap();
+  let split = divide(&board, 2);
+
+  assert_eq!(split.len(), 48);
+  assert_eq!(split.iter().map(|&(_, n)| n).sum::<u64>(), 2039);
+}
+
+#[test]
+fn promotions() {
+  check_depth("4k3/1P6/8/8/8/8/K7/8 w - - 0 1", 6, 217342);
+  check_depth("8/P1k5/K7/8/8/8/8/8 w - - 0 1", 6, 92683);
+}
+
+#[test]
+fn stalemate_and_checkmate() {
+  check_depth("K1k5/8/P7/8/8/8/8/8 w - - 0 1", 6, 2217);
+  check_depth("8/8/2k5/5q2/5n2/8/5K2/8 b - - 0 1", 4, 23527);
+}
+
+// The remaining edge cases are a few million nodes each; they take a while
+// without optimizations, so run them with `cargo test --release -- --ignored`.
+
+#[test]
+#[ignore]
+fn deep_reference_positions() {
+  check_depth(STARTING_FEN, 5, 4865609);
+  check_depth(KIWIPETE, 4, 4085603);
+  check_depth(POSITION_3, 5, 674624);
+  check_depth(POSITION_4, 4, 422333);
+  check_depth(POSITION_5, 4, 2103487);
+  check_depth(POSITION_6, 4, 3894594);
+}
+
+#[test]
+#[ignore]
+fn en_passant_edge_cases() {
+  // en passant would expose the king to a rook
+  check_depth("3k4/3p4/8/K1P4r/8/8/8/8 b - - 0 1", 6, 1134888);
+  check_depth("8/8/4k3/8/2p5/8/B2P2K1/8 w - - 0 1", 6, 1015133);
+  // en passant capture gives check
+  check_depth("8/8/1k6/2b5/2pP4/8/5K2/8 b - d3 0 1", 6, 1440467);
+}
+
+#[test]
+#[ignore]
+fn castling_edge_cases() {
+  // castling gives check
+  check_depth("5k2/8/8/8/8/8/8/4K2R w K - 0 1", 6, 661072);
+  check_depth("3k4/8/8/8/8/8/8/R3K3 w Q - 0 1", 6, 803711);
+  // castling rights and castling prevented by attacked squares
+  check_depth("r3k2r/1b4bq/8/8/8/8/7B/R3K2R w KQkq - 0 1", 4, 1274206);
+  check_depth("r3k2r/8/3Q4/8/8/5q2/8/R3K2R b KQkq - 0 1", 4, 1720476);
+}
+
+#[test]
+#[ignore]
+fn check_edge_cases() {
+  // promote out of check
+  check_depth("2K2r2/4P3/8/8/8/8/8/3k4 w - - 0 1", 6, 3821001);
+  // discovered check
+  check_depth("8/8/1P2K3/8/2n5/1q6/8/5k2 b - - 0 1", 5, 1004658);
+  check_depth("8/k1P5/8/1K6/8/8/8/8 w - - 0 1", 7, 567584);
+}
@@ -0,0 +1,310 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use chess::pgn::{pgn_date, PGN_LINE_LENGTH};
+use chess::{
+  parse_pgn, parse_pgn_games, Board, Game, GameResult, PgnError, PgnErrorKind, SanError,
+};
+
+fn play(game: &mut Game, sans: &str) {
+  for san in sans.split_whitespace() {
+    let mv = game.board().parse_san(san).unwrap();
+    game.push(mv);
+  }
+}
+
+#[test]
+fn fools_mate() {
+  let mut game = Game::new();
+  game.set_tag("Event", "Test \"quoted\"");
+  game.set_tag("Annotator", "nobody");
+  play(&mut game, "f3 e5 g4 Qh4");
+
+  assert_eq!(game.result(), Some(GameResult::BlackWins));
+  assert_eq!(
+    game.to_pgn(),
+    "[Event \"Test \\\"quoted\\\"\"]\n\
+     [Site \"?\"]\n\
+     [Date \"????.??.??\"]\n\
+     [Round \"?\"]\n\
+     [White \"?\"]\n\
+     [Black \"?\"]\n\
+     [Result \"0-1\"]\n\
+     [Annotator \"nobody\"]\n\
+     \n\
+     1. f3 e5 2. g4 Qh4# 0-1\n\
+     \n"
+  );
+
+  // taking back the mate reopens the game
+  game.pop();
+  assert_eq!(game.result(), None);
+  assert!(game.to_pgn().ends_with("1. f3 e5 2. g4 *\n\n"));
+}
+
+#[test]
+fn setup_position_and_black_to_move() {
+  let start = Board::from_fen("4k3/8/8/8/8/8/4p3/K7 b - - 0 40").unwrap();
+  let mut game = Game::from_position(start);
+  play(&mut game, "e1=Q+ Ka2 Qb1+");
+
+  let pgn = game.to_pgn();
+  assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4p3/K7 b - - 0 40\"]\n"));
+  assert!(pgn.ends_with("\n40... e1=Q+ 41. Ka2 Qb1+ *\n\n"));
+}
+
+#[test]
+fn movetext_is_wrapped() {
+  let mut game = Game::new();
+  for _ in 0..20 {
+    play(&mut game, "Nf3 Nf6 Ng1 Ng8");
+  }
+  // threefold repetition long since
+  assert_eq!(game.result(), Some(GameResult::Draw));
+
+  let pgn = game.to_pgn();
+  let movetext = pgn.split("\n\n").nth(1).unwrap();
+  assert!(movetext.lines().count() > 1);
+  for line in movetext.lines() {
+    assert!(line.len() <= PGN_LINE_LENGTH, "{line}");
+    assert!(!line.starts_with(' ') && !line.ends_with(' '));
+  }
+  assert_eq!(
+    movetext.split_whitespace().count(),
+    80 + 40 + 1,
+    "moves, move numbers and the result"
+  );
+  assert!(movetext.ends_with("1/2-1/2"));
+}
+
+#[test]
+fn dates() {
+  assert_eq!(pgn_date(UNIX_EPOCH), "1970.01.01");
+  let day = Duration::from_secs(86400);
+  assert_eq!(pgn_date(UNIX_EPOCH + day * 11016), "2000.02.29");
+  assert_eq!(pgn_date(UNIX_EPOCH + day * 20744 + day / 2), "2026.10.18");
+}
+
+const TWO_GAMES: &str = r#"% exported by hand
+[Event "Casual \"blitz\""]
+[White "Morphy"]
+[Black "Allies"]
+[Result "1-0"]
+
+1. e4 e5 2. Nf3 d6 3. d4 Bg4 {This is a weak move
+already.--Fischer} 4. dxe5 Bxf3 5. Qxf3 dxe5 6. Bc4 Nf6 7. Qb3 Qe7
+8. Nc3 c6 9. Bg5 $4 (9. Be3 $1 (9. O-O) 9... b5 ; a comment to the end of the line
+10. Bxb5) b5! 10. Nxb5 cxb5 11. Bxb5+ Nbd7 12. O-O-O Rd8 13. Rxd7 Rxd7
+14. Rd1 Qe6 15. Bxd7+ Nxd7 16. Qb8+ Nxb8 17. Rd8# 1-0
+
+[Event "Setup"]
+[SetUp "1"]
+[FEN "4k3/8/8/8/8/8/4p3/K7 b - - 0 40"]
+
+40...e1=Q+ 41.Ka2 1/2-1/2
+"#;
+
+#[test]
+fn multi_game_file() {
+  let games = parse_pgn(TWO_GAMES).unwrap();
+  assert_eq!(games.len(), 2);
+
+  let opera = &games[0];
+  assert_eq!(opera.tag("Event"), Some("Casual \"blitz\""));
+  assert_eq!(opera.tag("White"), Some("Morphy"));
+  assert_eq!(opera.moves().len(), 33);
+  assert_eq!(opera.result(), Some(GameResult::WhiteWins));
+  assert_eq!(
+    opera.board().to_fen(),
+    "1n1Rkb1r/p4ppp/4q3/4p1B1/4P3/8/PPP2PPP/2K5 b k - 1 17"
+  );
+
+  // a draw agreed in a position that isn't one
+  let setup = &games[1];
+  assert_eq!(setup.moves().len(), 2);
+  assert_eq!(setup.start().fullmove_number(), 40);
+  assert_eq!(setup.result(), Some(GameResult::Draw));
+  assert!(setup.to_pgn().ends_with("40... e1=Q+ 41. Ka2 1/2-1/2\n\n"));
+}
+
+#[test]
+fn export_reads_back() {
+  let games = parse_pgn(TWO_GAMES).unwrap();
+  let exported: String = games.iter().map(Game::to_pgn).collect();
+  let again = parse_pgn(&exported).unwrap();
+
+  assert_eq!(again.len(), games.len());
+  for (a, b) in games.iter().zip(&again) {
+    assert_eq!(a.moves(), b.moves());
+    assert_eq!(a.result(), b.result());
+    assert_eq!(a.to_pgn(), b.to_pgn());
+  }
+
+  // no tags at all, and no result either
+  let bare = parse_pgn("1.e4 e5 2.Nf3").unwrap();
+  assert_eq!(bare.len(), 1);
+  assert_eq!(bare[0].moves().len(), 3);
+  assert_eq!(bare[0].result(), None);
+}
+
+#[test]
+fn claimable_draws_need_a_claim() {
+  // Black resigned in a threefold repetition nobody claimed
+  let pgn = "[Result \"1-0\"]\n\n1. Nf3 Nf6 2. Ng1 Ng8 3. Nf3 Nf6 4. Ng1 Ng8 1-0\n";
+  let game = parse_pgn(pgn).unwrap().remove(0);
+  assert!(game.status().is_claimable());
+  assert_eq!(game.result(), Some(GameResult::WhiteWins));
+  assert!(game.to_pgn().ends_with(" 1-0\n\n"));
+
+  // and without a declared result the game is still going
+  let mut game = Game::new();
+  play(&mut game, "Nf3 Nf6 Ng1 Ng8 Nf3 Nf6 Ng1 Ng8");
+  assert_eq!(game.result(), None);
+  game.set_declared_result(Some(GameResult::Draw));
+  assert_eq!(game.result(), Some(GameResult::Draw));
+}
+
+fn error(pgn: &str) -> PgnError {
+  parse_pgn(pgn).unwrap_err()
+}
+
+#[test]
+fn errors_have_positions() {
+  assert_eq!(
+    error("[Event \"x\"]\n\n1. e4 e5 2. Ke3 *"),
+    PgnError {
+      line: 3,
+      column: 13,
+      kind: PgnErrorKind::IllegalMove(SanError::IllegalMove("Ke3".to_string())),
+    }
+  );
+
+  // moves in variations are checked against the position they branch off
+  let err = error("1. e4 e5 (1... Nf6 2. Nf6) 2. Nf3 *");
+  assert_eq!((err.line, err.column), (1, 23));
+  assert_eq!(
+    err.to_string(),
+    "line 1, column 23: illegal move in this position: 'Nf6'"
+  );
+
+  assert_eq!(
+    error("1. e4 {never closed").kind,
+    PgnErrorKind::UnterminatedComment
+  );
+  assert_eq!(
+    error("1. e4 (1. d4 *").kind,
+    PgnErrorKind::UnterminatedVariation
+  );
+  assert_eq!(
+    error("(1. d4) 1. e4 *").kind,
+    PgnErrorKind::MisplacedVariation
+  );
+  assert_eq!(
+    error("[Event \"x]\n1. e4").kind,
+    PgnErrorKind::UnterminatedString
+  );
+  assert!(matches!(
+    error("[FEN \"not a fen\"]\n*").kind,
+    PgnErrorKind::InvalidFen(_)
+  ));
+  assert_eq!(
+    error("1. e4 ) *").kind,
+    PgnErrorKind::UnexpectedToken(")".to_string())
+  );
+}
+
+#[test]
+fn annotations_round_trip() {
+  let games = parse_pgn(TWO_GAMES).unwrap();
+  let opera = &games[0];
+  let annotations = opera.annotations();
+  assert_eq!(annotations.len(), opera.moves().len());
+
+  // 3... Bg4
+  assert_eq!(
+    annotations[5].comments,
+    ["This is a weak move already.--Fischer"]
+  );
+  // 9. Bg5 $4 (9. Be3 $1 (9. O-O) 9... b5 ; ... 10. Bxb5) b5!
+  let bg5 = &annotations[16];
+  assert_eq!(bg5.nags, [4]);
+  assert_eq!(bg5.variations.len(), 1);
+  let line = &bg5.variations[0];
+  let board = &opera.positions()[16];
+  let sans: Vec<String> = line
+    .moves
+    .iter()
+    .scan(*board, |board, &(mv, _)| {
+      let san = mv.to_san(board);
+      *board = board.apply_move(mv);
+      Some(san)
+    })
+    .collect();
+  assert_eq!(sans, ["Be3", "b5", "Bxb5"]);
+  assert_eq!(line.moves[0].1.nags, [1]);
+  assert_eq!(line.moves[0].1.variations[0].moves.len(), 1);
+  assert_eq!(
+    line.moves[1].1.comments,
+    ["a comment to the end of the line"]
+  );
+  assert_eq!(annotations[17].nags, [1]);
+  assert!(games[1]
+    .annotations()
+    .iter()
+    .all(|a| *a == Default::default()));
+
+  let pgn = opera.to_pgn();
+  let movetext = pgn.split_whitespace().collect::<Vec<_>>().join(" ");
+  assert!(movetext.contains("3. d4 Bg4 {This is a weak move already.--Fischer} 4. dxe5 Bxf3"));
+  assert!(movetext.contains(
+    "9. Bg5 $4 (9. Be3 $1 (9. O-O) 9... b5 {a comment to the end of the line} \
+     10. Bxb5) 9... b5 $1 10. Nxb5"
+  ));
+
+  let again = parse_pgn(&pgn).unwrap().remove(0);
+  assert_eq!(again.annotations(), annotations);
+  assert_eq!(again.to_pgn(), pgn);
+
+  // comments before the first move and a `}` that needs a `;` comment
+  let text = "{Before} [Event \"x\"]\n{the game} 1. e4 (; a } brace\n1. d4) e5 $10 *";
+  let game = parse_pgn(text).unwrap().remove(0);
+  assert_eq!(game.comments(), ["Before", "the game"]);
+  assert_eq!(game.annotations()[0].variations[0].comments, ["a } brace"]);
+  let pgn = game.to_pgn();
+  assert!(pgn.ends_with("\n{Before} {the game} 1. e4 ( ;a } brace\n1. d4) 1... e5 $10 *\n\n"));
+  let again = parse_pgn(&pgn).unwrap().remove(0);
+  assert_eq!(again.comments(), game.comments());
+  assert_eq!(again.annotations(), game.annotations());
+
+  // a comment after the last game isn't a game of its own
+  assert_eq!(parse_pgn("1. e4 * {the end}").unwrap().len(), 1);
+}
+
+#[test]
+fn bad_games_are_skipped() {
+  let pgn = "[Event \"bad tag]\n[Site \"x\"]\n\n1. e4 e5 *\n\n\
+             [Event \"good\"]\n\n1. d4 d5 *\n\n\
+             [Event \"illegal\"]\n\n1. e4 e5 2. Ke3 *\n\
+             [Event \"last\"]\n\n1. c4 *\n";
+  let games = parse_pgn_games(pgn);
+  assert_eq!(games.len(), 4);
+
+  assert_eq!(
+    games[0].as_ref().unwrap_err().kind,
+    PgnErrorKind::UnterminatedString
+  );
+  assert_eq!(games[1].as_ref().unwrap().tag("Event"), Some("good"));
+  let err = games[2].as_ref().unwrap_err();
+  assert_eq!((err.line, err.column), (12, 13));
+  assert_eq!(games[3].as_ref().unwrap().tag("Event"), Some("last"));
+
+  // the good games are the same as parse_pgn gives when there is no bad one
+  let good = parse_pgn(TWO_GAMES).unwrap();
+  let each: Vec<Game> = parse_pgn_games(TWO_GAMES)
+    .into_iter()
+    .map(Result::unwrap)
+    .collect();
+  assert_eq!(each.len(), good.len());
+  for (a, b) in each.iter().zip(&good) {
+    assert_eq!(a.to_pgn(), b.to_pgn());
+  }
+}
@@ -0,0 +1,132 @@
+use chess::polyglot::{decode_move, encode_move};
+use chess::{polyglot_key, Board, Book};
+
+// the example keys from the Polyglot book format's specification
+const KEYS: [(&str, u64); 9] = [
+  (
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    0x463b96181691fc9c,
+  ),
+  (
+    "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+    0x823c9b50fd114196,
+  ),
+  (
+    "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2",
+    0x0756b94461c50fb0,
+  ),
+  (
+    "rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 2",
+    0x662fafb965db29d4,
+  ),
+  (
+    "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+    0x22a48b5a8e47ff78,
+  ),
+  (
+    "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPPKPPP/RNBQ1BNR b kq - 0 3",
+    0x652a607ca3f242c1,
+  ),
+  (
+    "rnbq1bnr/ppp1pkpp/8/3pPp2/8/8/PPPPKPPP/RNBQ1BNR w - - 0 4",
+    0x00fdd303c946bdd9,
+  ),
+  (
+    "rnbqkbnr/p1pppppp/8/8/PpP4P/8/1P1PPPP1/RNBQKBNR b KQkq c3 0 3",
+    0x3c8123ea7b067637,
+  ),
+  (
+    "rnbqkbnr/p1pppppp/8/8/P6P/R1p5/1P1PPPP1/1NBQKBNR b Kkq - 0 4",
+    0x5c3f9b829b279560,
+  ),
+];
+
+// a Polyglot entry: big-endian key, move, weight and a learn field
+fn entry(key: u64, mv: u16, weight: u16) -> Vec<u8> {
+  let mut bytes = key.to_be_bytes().to_vec();
+  bytes.extend_from_slice(&mv.to_be_bytes());
+  bytes.extend_from_slice(&weight.to_be_bytes());
+  bytes.extend_from_slice(&[0; 4]);
+  bytes
+}
+
+fn sans(book: &Book, board: &Board) -> Vec<(String, u16)> {
+  book
+    .moves(board)
+    .into_iter()
+    .map(|(mv, weight)| (mv.to_san(board), weight))
+    .collect()
+}
+
+#[test]
+fn keys_match_the_specification() {
+  for (fen, key) in KEYS {
+    assert_eq!(polyglot_key(&Board::from_fen(fen).unwrap()), key, "{fen}");
+  }
+  assert_eq!(polyglot_key(&Board::new()), KEYS[0].1);
+}
+
+#[test]
+fn moves_round_trip() {
+  for fen in [
+    chess::STARTING_FEN,
+    "r3k2r/1P4P1/8/8/8/8/1p4p1/R3K2R w KQkq - 5 40",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+  ] {
+    let board = Board::from_fen(fen).unwrap();
+    for mv in board.moves_for_player() {
+      assert_eq!(decode_move(&board, encode_move(mv)), Some(mv), "{fen}");
+    }
+  }
+}
+
+#[test]
+fn reads_polyglot_books() {
+  let start = Board::new();
+  let castling = Board::from_fen("r3k2r/1P6/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+  let mut bytes = Vec::new();
+  // castling is written as the king taking its rook: e1h1 and e1a1
+  bytes.extend(entry(polyglot_key(&castling), 0x0107, 5));
+  bytes.extend(entry(polyglot_key(&castling), 0x0100, 1));
+  // b7b8q
+  bytes.extend(entry(polyglot_key(&castling), 0x4c79, 2));
+  // d2d4, then e2e4, deliberately out of order
+  bytes.extend(entry(KEYS[0].1, 0x02db, 1));
+  bytes.extend(entry(KEYS[0].1, 0x031c, 3));
+  // a move that isn't legal in the position is skipped
+  bytes.extend(entry(KEYS[0].1, 0x0000, 9));
+
+  let book = Book::from_polyglot_bytes(&bytes).unwrap();
+  assert_eq!(book.len(), 6);
+  assert_eq!(
+    sans(&book, &start),
+    [("e4".to_string(), 3), ("d4".to_string(), 1)]
+  );
+  assert_eq!(
+    sans(&book, &castling),
+    [
+      ("O-O".to_string(), 5),
+      ("b8=Q+".to_string(), 2),
+      ("O-O-O".to_string(), 1)
+    ]
+  );
+
+  // the weighted pick works the same as for native books
+  let picks: Vec<String> = (0..4)
+    .map(|r| book.pick(&start, r).unwrap().to_san(&start))
+    .collect();
+  assert_eq!(picks, ["e4", "e4", "e4", "d4"]);
+
+  // load tells the formats apart, and a Polyglot book is written back as is
+  let path = std::env::temp_dir().join(format!("chess-polyglot-{}.bin", std::process::id()));
+  std::fs::write(&path, &bytes).unwrap();
+  let loaded = Book::load(&path);
+  std::fs::remove_file(&path).unwrap();
+  let loaded = loaded.unwrap();
+  assert_eq!(sans(&loaded, &start), sans(&book, &start));
+  assert_eq!(
+    Book::from_polyglot_bytes(&loaded.to_bytes()).unwrap().len(),
+    6
+  );
+}
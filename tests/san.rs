@@ -0,0 +1,132 @@
+use chess::{Board, SanError, STARTING_FEN};
+
+fn sans(fen: &str) -> Vec<String> {
+  let board = Board::from_fen(fen).unwrap();
+  let mut sans: Vec<String> = board
+    .moves_for_player()
+    .iter()
+    .map(|mv| mv.to_san(&board))
+    .collect();
+  sans.sort();
+  sans
+}
+
+fn san_of(fen: &str, san: &str) -> String {
+  let board = Board::from_fen(fen).unwrap();
+  board.parse_san(san).unwrap().to_san(&board)
+}
+
+#[test]
+fn every_legal_move_round_trips() {
+  for fen in [
+    STARTING_FEN,
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "2k5/8/8/8/4Q2Q/8/K7/7Q w - - 0 1",
+  ] {
+    let board = Board::from_fen(fen).unwrap();
+    let moves = board.moves_for_player();
+    for &mv in moves.iter() {
+      let san = mv.to_san(&board);
+      assert_eq!(board.parse_san(&san), Ok(mv), "{san} in {fen}");
+    }
+
+    // and no two moves share a name
+    let mut all = sans(fen);
+    all.dedup();
+    assert_eq!(all.len(), moves.len(), "{fen}");
+  }
+}
+
+#[test]
+fn formatting() {
+  assert!(sans(STARTING_FEN).contains(&"Nf3".to_string()));
+  assert!(sans(STARTING_FEN).contains(&"e4".to_string()));
+
+  // disambiguation by file, by rank, and by both
+  let knights = sans("4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1");
+  assert!(knights.contains(&"Nbd2".to_string()) && knights.contains(&"Nfd2".to_string()));
+  let rooks = sans("4k3/8/8/R7/8/8/8/R3K3 w - - 0 1");
+  assert!(rooks.contains(&"R1a3".to_string()) && rooks.contains(&"R5a3".to_string()));
+  let queens = sans("2k5/8/8/8/4Q2Q/8/K7/7Q w - - 0 1");
+  for san in ["Qh4e1", "Qee1", "Q1e1"] {
+    assert!(queens.contains(&san.to_string()), "{san}");
+  }
+
+  // castling, captures, promotion, check and mate
+  let kiwipete = sans("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+  for san in ["O-O", "O-O-O", "Nxf7", "Qxf6", "gxh3", "dxe6"] {
+    assert!(kiwipete.contains(&san.to_string()), "{san}");
+  }
+  assert!(sans("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1").contains(&"axb8=Q+".to_string()));
+  assert!(
+    sans("rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 2")
+      .contains(&"Qh4#".to_string())
+  );
+}
+
+#[test]
+fn lenient_parsing() {
+  let kiwipete = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+  assert_eq!(san_of(kiwipete, "0-0"), "O-O");
+  assert_eq!(san_of(kiwipete, "0-0-0!"), "O-O-O");
+  assert_eq!(san_of(kiwipete, "o-o"), "O-O");
+  assert_eq!(san_of(kiwipete, "o-o-o+"), "O-O-O");
+  assert_eq!(san_of(kiwipete, "Nf7"), "Nxf7");
+  assert_eq!(san_of(kiwipete, "Ne5xf7?!"), "Nxf7");
+  assert_eq!(san_of(kiwipete, "gh3"), "gxh3");
+  assert_eq!(san_of(STARTING_FEN, "Ng1f3+"), "Nf3");
+  assert_eq!(san_of("4k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a8Q"), "a8=Q+");
+  assert_eq!(san_of("4k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a8=n"), "a8=N");
+  assert_eq!(san_of("4k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a8q"), "a8=Q+");
+  assert_eq!(
+    san_of("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1", "axb8r"),
+    "axb8=R+"
+  );
+  assert_eq!(
+    san_of("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2", "exd6 e.p."),
+    "exd6"
+  );
+}
+
+#[test]
+fn parse_errors() {
+  let board = Board::new();
+  assert_eq!(
+    board.parse_san("Ze4"),
+    Err(SanError::Syntax("Ze4".to_string()))
+  );
+  assert_eq!(board.parse_san(""), Err(SanError::Syntax(String::new())));
+  assert_eq!(
+    board.parse_san("e5"),
+    Err(SanError::IllegalMove("e5".to_string()))
+  );
+  assert_eq!(
+    board.parse_san("O-O"),
+    Err(SanError::IllegalMove("O-O".to_string()))
+  );
+
+  // promotions need a piece
+  let promo = Board::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+  assert!(matches!(
+    promo.parse_san("a8"),
+    Err(SanError::IllegalMove(_))
+  ));
+
+  let knights = Board::from_fen("4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1").unwrap();
+  let err = knights.parse_san("Nd2").unwrap_err();
+  assert_eq!(
+    err,
+    SanError::AmbiguousMove {
+      san: "Nd2".to_string(),
+      candidates: vec!["Nfd2".to_string(), "Nbd2".to_string()],
+    }
+  );
+  assert_eq!(
+    err.to_string(),
+    "ambiguous move 'Nd2', could be any of: Nfd2, Nbd2"
+  );
+}
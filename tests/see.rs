@@ -0,0 +1,76 @@
+use chess::{Board, Move};
+
+fn see(fen: &str, from: (u32, u32), to: (u32, u32)) -> i32 {
+  let board = Board::from_fen(fen).unwrap();
+  let mv: Move = *board
+    .moves_for_player()
+    .iter()
+    .find(|mv| {
+      mv.from() == from
+        && mv.to() == to
+        && mv.promotion().is_none_or(|p| p == chess::PieceType::Queen)
+    })
+    .unwrap_or_else(|| panic!("no move from {from:?} to {to:?}"));
+  board.see(mv)
+}
+
+#[test]
+fn simple_exchanges() {
+  // Rxe5 wins an undefended pawn
+  assert_eq!(
+    see(
+      "1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1",
+      (4, 7),
+      (4, 3)
+    ),
+    100
+  );
+
+  // Nxe5 Nxe5 Rxe5 Bxe5 Qxe5 Qxe5: both queens join in from behind the
+  // rook and bishop, and white loses the knight for a pawn
+  assert_eq!(
+    see(
+      "1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1",
+      (3, 5),
+      (4, 3)
+    ),
+    -220
+  );
+
+  // queen to a square a pawn covers
+  assert_eq!(
+    see("4k3/8/4p3/8/8/8/8/3QK3 w - - 0 1", (3, 7), (3, 3)),
+    -900
+  );
+
+  // en passant takes the pawn behind the target square
+  assert_eq!(
+    see("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2", (4, 3), (3, 2)),
+    100
+  );
+}
+
+#[test]
+fn king_only_recaptures_undefended_pieces() {
+  // the king takes the rook back
+  assert_eq!(
+    see("8/8/2k5/3p4/8/8/8/3R2K1 w - - 0 1", (3, 7), (3, 3)),
+    -400
+  );
+  // but not with the queen behind it
+  assert_eq!(
+    see("8/8/2k5/3p4/8/8/3R4/3Q2K1 w - - 0 1", (3, 6), (3, 3)),
+    100
+  );
+}
+
+#[test]
+fn promotions_count_the_new_piece() {
+  // a8=Q wins 800 on its own
+  assert_eq!(see("4k3/P7/8/8/8/8/8/4K3 w - - 0 1", (0, 1), (0, 0)), 800);
+  // but loses the pawn if the rook takes the queen
+  assert_eq!(
+    see("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1", (0, 1), (0, 0)),
+    -100
+  );
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use chess::{Board, GameResult, GameStatus, PieceColor, STARTING_FEN};
+use common::play;
+
+fn status(fen: &str) -> GameStatus {
+  Board::from_fen(fen).unwrap().status(&[])
+}
+
+#[test]
+fn checkmate_and_stalemate_are_distinct() {
+  // fool's mate
+  assert_eq!(
+    status("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3"),
+    GameStatus::Checkmate {
+      winner: PieceColor::Black
+    }
+  );
+  assert_eq!(
+    status("k7/8/1Q6/8/8/8/8/7K b - - 0 1"),
+    GameStatus::Stalemate
+  );
+  assert_eq!(
+    status("k7/8/1Q6/8/8/8/8/7K b - - 0 1").result(),
+    Some(GameResult::Draw)
+  );
+  assert_eq!(status(chess::STARTING_FEN), GameStatus::Ongoing);
+}
+
+#[test]
+fn stalemate_is_not_scored_as_mate() {
+  let board = Board::from_fen("k7/8/1Q6/8/8/8/8/7K b - - 0 1").unwrap();
+  assert_eq!(board.eval(), 0);
+  assert!(!chess::is_in_checkmate(&board));
+  assert!(chess::is_in_stalemate(&board));
+}
+
+#[test]
+fn move_count_rules() {
+  assert_eq!(
+    status("4k3/8/8/8/8/8/8/R3K3 w - - 99 80"),
+    GameStatus::Ongoing
+  );
+  let fifty = status("4k3/8/8/8/8/8/8/R3K3 w - - 100 80");
+  assert_eq!(fifty, GameStatus::FiftyMoveRule);
+  assert!(fifty.is_claimable());
+  let seventy_five = status("4k3/8/8/8/8/8/8/R3K3 w - - 150 100");
+  assert_eq!(seventy_five, GameStatus::SeventyFiveMoveRule);
+  assert!(!seventy_five.is_claimable());
+
+  // mate on the last move still counts as mate
+  assert_eq!(
+    status("R3k3/8/4K3/8/8/8/8/8 b - - 150 100"),
+    GameStatus::Checkmate {
+      winner: PieceColor::White
+    }
+  );
+}
+
+#[test]
+fn insufficient_material() {
+  for fen in [
+    "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+    "4k3/8/8/8/8/8/8/4KN2 w - - 0 1",
+    "4kb2/8/8/8/8/8/8/4K3 w - - 0 1",
+    // bishops on the same square color
+    "4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1",
+  ] {
+    assert_eq!(status(fen), GameStatus::InsufficientMaterial, "{fen}");
+  }
+
+  for fen in [
+    "4k3/8/8/8/8/8/8/3NKN2 w - - 0 1",
+    "4kb2/8/8/8/8/8/8/4KB2 w - - 0 1",
+    "4k3/8/8/8/8/8/8/3BKN2 w - - 0 1",
+    "4k3/8/8/8/8/8/P7/4K3 w - - 0 1",
+  ] {
+    assert_eq!(status(fen), GameStatus::Ongoing, "{fen}");
+  }
+}
+
+#[test]
+fn repetition() {
+  let mut board = Board::new();
+  let mut history = vec![];
+
+  // knights out and back: Nf3 Nf6 Ng1 Ng8
+  let shuffle = [
+    ((6, 7), (5, 5)),
+    ((6, 0), (5, 2)),
+    ((5, 5), (6, 7)),
+    ((5, 2), (6, 0)),
+  ];
+
+  let mut statuses = vec![];
+  for _ in 0..4 {
+    for (from, to) in shuffle {
+      history.push(board);
+      board = play(&board, from, to);
+    }
+    statuses.push((board.repetition_count(&history), board.status(&history)));
+  }
+
+  assert_eq!(
+    statuses,
+    [
+      (2, GameStatus::Ongoing),
+      (3, GameStatus::ThreefoldRepetition),
+      (4, GameStatus::ThreefoldRepetition),
+      (5, GameStatus::FivefoldRepetition),
+    ]
+  );
+}
+
+// the positions after each move of `moves` from `fen`, and the history
+// before the last one
+fn after_san(fen: &str, moves: &str) -> (Board, Vec<Board>) {
+  let mut board = Board::from_fen(fen).unwrap();
+  let mut history = vec![];
+  for san in moves.split_whitespace() {
+    history.push(board);
+    board = board.apply_move(board.parse_san(san).unwrap());
+  }
+  (board, history)
+}
+
+#[test]
+fn repetition_ignores_unusable_en_passant_squares() {
+  // after 1.e4 Black can't take en passant, so 3.Ng1 and 5.Ng1 repeat it
+  let (board, history) = after_san(STARTING_FEN, "e4 Nf6 Nf3 Ng8 Ng1 Nf6 Nf3 Ng8 Ng1");
+  assert_eq!(board.repetition_count(&history), 3);
+  assert_eq!(board.status(&history), GameStatus::ThreefoldRepetition);
+
+  // here it could, so the position after e4 never comes back
+  let (board, history) = after_san(
+    "4k3/8/8/8/3p4/8/4P3/4K3 w - - 0 1",
+    "e4 Ke7 Ke2 Ke8 Ke1 Ke7 Ke2 Ke8 Ke1",
+  );
+  assert_eq!(board.repetition_count(&history), 2);
+  assert_eq!(board.status(&history), GameStatus::Ongoing);
+
+  // nor while the capturing pawn is pinned
+  let (board, history) = after_san(
+    "8/8/8/8/k2p3R/8/4P3/4K3 w - - 0 1",
+    "e4 Ka5 Kd1 Ka4 Ke1 Ka5 Kd1 Ka4 Ke1",
+  );
+  assert_eq!(board.repetition_count(&history), 3);
+}
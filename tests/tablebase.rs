@@ -0,0 +1,200 @@
+use std::sync::OnceLock;
+
+use chess::search::minimax_with_tablebase;
+use chess::{Board, GameStatus, Outcome, PieceColor, Tablebase, TablebaseError};
+
+fn tablebase() -> &'static Tablebase {
+  static TABLEBASE: OnceLock<Tablebase> = OnceLock::new();
+  TABLEBASE.get_or_init(|| {
+    let mut tablebase = Tablebase::new();
+    for material in ["KQK", "KRK", "KPK"] {
+      tablebase.generate(material).unwrap();
+    }
+    tablebase
+  })
+}
+
+fn probe(fen: &str) -> Option<Outcome> {
+  tablebase().probe(&Board::from_fen(fen).unwrap())
+}
+
+#[test]
+fn probes() {
+  assert_eq!(
+    probe("k7/8/1K6/8/8/8/7Q/8 w - - 0 1"),
+    Some(Outcome::Win(1))
+  );
+  assert_eq!(
+    probe("k6Q/8/1K6/8/8/8/8/8 b - - 0 1"),
+    Some(Outcome::Loss(0))
+  );
+  // the same with colors swapped
+  assert_eq!(
+    probe("8/7q/8/8/8/1k6/8/K7 b - - 0 1"),
+    Some(Outcome::Win(1))
+  );
+  // stalemate
+  assert_eq!(probe("k7/2Q5/1K6/8/8/8/8/8 b - - 0 1"), Some(Outcome::Draw));
+  // a rook pawn the defending king gets in front of
+  assert_eq!(probe("k7/8/8/8/8/8/P7/K7 w - - 0 1"), Some(Outcome::Draw));
+  assert_eq!(
+    probe("8/8/8/4k3/8/8/4P3/4K3 w - - 0 1"),
+    Some(Outcome::Draw)
+  );
+  assert_eq!(
+    probe("8/8/8/8/4k3/8/4P3/4K3 b - - 0 1"),
+    Some(Outcome::Draw)
+  );
+  assert!(matches!(
+    probe("4k3/8/4K3/4P3/8/8/8/8 w - - 0 1"),
+    Some(Outcome::Win(_))
+  ));
+
+  // bare kings don't need a table
+  assert_eq!(probe("k7/8/8/8/8/8/8/K7 w - - 0 1"), Some(Outcome::Draw));
+  // no table for these
+  assert_eq!(probe("k7/8/8/8/8/8/8/KBN5 w - - 0 1"), None);
+  assert_eq!(
+    probe("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+    None
+  );
+}
+
+// FEN of a position with one piece of each kind, given as (FEN letter,
+// square) where squares count from a8 to h1
+fn fen(pieces: &[(char, usize)], side: char) -> String {
+  let mut ranks = Vec::new();
+  for rank in 0..8 {
+    let mut text = String::new();
+    let mut empty = 0;
+    for sq in 8 * rank..8 * rank + 8 {
+      match pieces.iter().find(|&&(_, s)| s == sq) {
+        Some(&(c, _)) => {
+          if empty > 0 {
+            text += &empty.to_string();
+            empty = 0;
+          }
+          text.push(c);
+        }
+        None => empty += 1,
+      }
+    }
+    if empty > 0 {
+      text += &empty.to_string();
+    }
+    ranks.push(text);
+  }
+  format!("{} {side} - - 0 1", ranks.join("/"))
+}
+
+// the longest win with White to move over every placement of the kings and
+// `piece`
+fn longest_win(tablebase: &Tablebase, piece: char) -> u32 {
+  let mut longest = 0;
+  for wk in 0..64 {
+    for sq in 0..64 {
+      for bk in 0..64 {
+        if wk == sq || wk == bk || sq == bk {
+          continue;
+        }
+        // from_fen turns down positions with Black in check
+        let Ok(board) = Board::from_fen(&fen(&[('K', wk), (piece, sq), ('k', bk)], 'w')) else {
+          continue;
+        };
+        if let Some(Outcome::Win(plies)) = tablebase.probe(&board) {
+          longest = longest.max(plies);
+        }
+      }
+    }
+  }
+  longest
+}
+
+#[test]
+fn longest_mates() {
+  // mate in 10 and mate in 16
+  assert_eq!(longest_win(tablebase(), 'Q'), 19);
+  assert_eq!(longest_win(tablebase(), 'R'), 31);
+}
+
+#[test]
+fn best_moves_mate() {
+  let mut board = Board::from_fen("8/8/8/4k3/8/8/8/R3K3 w - - 0 1").unwrap();
+  let Some(Outcome::Win(plies)) = tablebase().probe(&board) else {
+    panic!("KRK should be won");
+  };
+
+  // the search plays the tables' moves and scores
+  let (mv, score) = minimax_with_tablebase(board, &[], 1, tablebase());
+  assert_eq!(
+    (mv, score),
+    (
+      tablebase().best_move(&board).map(|(mv, _)| mv),
+      Outcome::Win(plies).score(PieceColor::White)
+    )
+  );
+
+  for ply in 0..plies {
+    let (mv, outcome) = tablebase().best_move(&board).unwrap();
+    // the winner takes the quickest path and the loser the slowest
+    let expected = if ply % 2 == 0 {
+      Outcome::Win(plies - ply)
+    } else {
+      Outcome::Loss(plies - ply)
+    };
+    assert_eq!(outcome, expected);
+    board = board.apply_move(mv);
+  }
+
+  assert_eq!(
+    board.status(&[]),
+    GameStatus::Checkmate {
+      winner: PieceColor::White
+    }
+  );
+  assert_eq!(tablebase().best_move(&board), None);
+}
+
+#[test]
+fn files_round_trip() {
+  let dir = std::env::temp_dir().join(format!("chess-tablebase-{}", std::process::id()));
+  tablebase().save_dir(&dir).unwrap();
+
+  let read = Tablebase::load_dir(&dir).unwrap();
+  assert_eq!(read.tables(), ["KPK", "KQK", "KRK"]);
+  assert_eq!(read.max_pieces(), 3);
+  let board = Board::from_fen("4k3/8/4K3/4P3/8/8/8/8 w - - 0 1").unwrap();
+  assert_eq!(read.probe(&board), tablebase().probe(&board));
+
+  std::fs::write(dir.join("junk.tb"), b"not a table").unwrap();
+  assert!(matches!(
+    Tablebase::load_dir(&dir),
+    Err(TablebaseError::InvalidFile(_))
+  ));
+  std::fs::remove_dir_all(&dir).unwrap();
+
+  assert!(matches!(
+    Tablebase::new().generate("KQKX"),
+    Err(TablebaseError::InvalidMaterial(_))
+  ));
+  assert!(matches!(
+    Tablebase::new().generate("KQRKQ"),
+    Err(TablebaseError::TooManyPieces(_))
+  ));
+  // en passant would matter here
+  assert!(matches!(
+    Tablebase::new().generate("KPKP"),
+    Err(TablebaseError::PawnsOnBothSides(_))
+  ));
+}
+
+// takes a while without optimizations
+#[test]
+#[ignore]
+fn bishop_and_knight() {
+  let mut tablebase = Tablebase::new();
+  tablebase.generate("KBNK").unwrap();
+  // one of the longest: mate in 33
+  let board = Board::from_fen("KNB5/2k5/8/8/8/8/8/8 w - - 0 1").unwrap();
+  assert_eq!(tablebase.probe(&board), Some(Outcome::Win(65)));
+}
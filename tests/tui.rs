@@ -0,0 +1,161 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const EN_PASSANT_CASTLING_PROMOTION: &str = "4k2r/1P6/8/3pP3/8/8/8/R3K2R w KQk d6 0 1";
+
+const BAD_FENS: [&str; 3] = [
+  // no pawn in front of the en passant square
+  "4k3/8/8/8/4p3/8/8/4K3 b - d3 0 1",
+  // a pawn on the back rank
+  "P3k3/8/8/8/8/8/8/4K3 w - - 0 1",
+  // the side not to move in check
+  "4k3/4R3/8/8/8/8/8/4K3 w - - 0 1",
+];
+
+struct Output {
+  success: bool,
+  stdout: String,
+  stderr: String,
+}
+
+// run the tui with `args`, typing `input`; it's run in an empty directory
+// so that no book or tables get loaded
+fn run(args: &[&str], input: &str, color: bool) -> Output {
+  let dir = std::env::temp_dir().join(format!("chess-tui-{}", std::process::id()));
+  std::fs::create_dir_all(&dir).unwrap();
+
+  let mut command = Command::new(env!("CARGO_BIN_EXE_tui"));
+  command
+    .args(args)
+    .current_dir(&dir)
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped());
+  if color {
+    command.env_remove("NO_COLOR");
+  } else {
+    command.arg("--no-color");
+  }
+
+  let mut child = command.spawn().unwrap();
+  // a tui that refuses its arguments exits without reading, so the pipe
+  // may be closed already
+  let _ = child.stdin.take().unwrap().write_all(input.as_bytes());
+  let output = child.wait_with_output().unwrap();
+
+  Output {
+    success: output.status.success(),
+    stdout: String::from_utf8(output.stdout).unwrap(),
+    stderr: String::from_utf8(output.stderr).unwrap(),
+  }
+}
+
+// the movetext of the game after typing `moves` from `fen`
+fn movetext(fen: &str, moves: &str) -> String {
+  let input = format!("{}\npgn\nquit\n", moves.replace(' ', "\n"));
+  let out = run(&[fen], &input, false);
+  assert!(out.success, "{}", out.stderr);
+  out
+    .stdout
+    .lines()
+    .find(|line| line.starts_with("1."))
+    .unwrap_or_default()
+    .to_string()
+}
+
+#[test]
+fn san_and_coordinate_moves() {
+  let expected = "1. exd6 O-O 2. O-O-O Kg7 3. b8=Q *";
+  assert_eq!(
+    movetext(EN_PASSANT_CASTLING_PROMOTION, "exd6 O-O O-O-O Kg7 b8=Q"),
+    expected
+  );
+  assert_eq!(
+    movetext(EN_PASSANT_CASTLING_PROMOTION, "e5d6 e8g8 e1c1 g8g7 b7b8q"),
+    expected
+  );
+}
+
+#[test]
+fn illegal_input_is_rejected() {
+  let out = run(&[], "e2e5\nNxe5\nxyz\ne7e8\nfen\nquit\n", false);
+  assert!(out.success);
+
+  let replies: Vec<&str> = out
+    .stdout
+    .lines()
+    .filter_map(|line| line.strip_prefix("White (1)> "))
+    .collect();
+  assert_eq!(
+    replies,
+    [
+      "the piece can't move like that (type help for the commands)",
+      "illegal move in this position: 'Nxe5' (type help for the commands)",
+      "invalid SAN: 'xyz' (type help for the commands)",
+      "the piece on e7 is not the side to move's (type help for the commands)",
+      "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+      "",
+    ]
+  );
+}
+
+#[test]
+fn invalid_fens_are_reported() {
+  for fen in BAD_FENS {
+    let out = run(&[], &format!("fen {fen}\nquit\n"), false);
+    assert!(out.success, "{fen}: {}", out.stderr);
+    assert!(out.stdout.contains("Invalid FEN: "), "{fen}");
+
+    let out = run(&[fen], "quit\n", false);
+    assert!(!out.success);
+    assert!(
+      out.stderr.starts_with("invalid FEN: "),
+      "{fen}: {}",
+      out.stderr
+    );
+  }
+}
+
+#[test]
+fn board_output() {
+  let out = run(&["4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"], "flip\nquit\n", false);
+  let expected = [
+    "",
+    " 8 . . . . k . . . ",
+    " 7 . . . . . . . . ",
+    " 6 . . . . . . . . ",
+    " 5 . . . . . . . . ",
+    " 4 . . . . . . . . ",
+    " 3 . . . . . . . . ",
+    " 2 . . . . P . . . ",
+    " 1 . . . . K . . . ",
+    "   a b c d e f g h",
+    // after flip
+    "White (1)> ",
+    " 1 . . . K . . . . ",
+    " 2 . . . P . . . . ",
+    " 3 . . . . . . . . ",
+    " 4 . . . . . . . . ",
+    " 5 . . . . . . . . ",
+    " 6 . . . . . . . . ",
+    " 7 . . . . . . . . ",
+    " 8 . . . k . . . . ",
+    "   h g f e d c b a",
+  ];
+  assert!(
+    out.stdout.starts_with(&expected.join("\n")),
+    "{}",
+    out.stdout
+  );
+
+  // in color, with the squares of the last move highlighted
+  let out = run(&[], "e4\nquit\n", true);
+  let (_, board) = out.stdout.split_once("> e4\n").unwrap();
+  let highlight = "\x1b[48;2;205;210;106m";
+  assert!(board.contains("\x1b[48;2;137;224;143m\x1b[1;38;2;0;0;0m \u{265c} \x1b[0m"));
+  assert!(board.contains(&format!(
+    "{highlight}\x1b[1;38;2;255;255;255m \u{265f} \x1b[0m"
+  )));
+  assert!(board.contains(&format!("{highlight}   \x1b[0m")));
+  assert_eq!(board.matches(highlight).count(), 2);
+}
@@ -0,0 +1,86 @@
+use chess::PieceType::*;
+use chess::{Board, Move, MoveKind, ParseMoveError, ParseSquareError, Square};
+
+#[test]
+fn square_names() {
+  let e4: Square = "e4".parse().unwrap();
+  assert_eq!(e4.coord(), (4, 4));
+  assert_eq!(e4.to_string(), "e4");
+
+  // y = 0 is rank 8
+  assert_eq!("a8".parse::<Square>().unwrap().index(), 0);
+  assert_eq!("h1".parse::<Square>().unwrap().index(), 63);
+  assert_eq!(Square::from_coord((0, 7)).unwrap().to_string(), "a1");
+
+  for idx in 0..64 {
+    let sq = Square::from_index(idx).unwrap();
+    assert_eq!(sq.to_string().parse::<Square>(), Ok(sq));
+  }
+
+  assert_eq!(Square::from_index(64), None);
+  assert_eq!(Square::from_coord((8, 0)), None);
+  for bad in ["", "e", "e9", "i1", "E4", "e44"] {
+    assert_eq!(
+      bad.parse::<Square>(),
+      Err(ParseSquareError(bad.to_string()))
+    );
+  }
+}
+
+#[test]
+fn move_strings() {
+  let mv: Move = "e2e4".parse().unwrap();
+  assert_eq!(
+    (mv.from(), mv.to(), mv.kind()),
+    ((4, 6), (4, 4), MoveKind::Quiet)
+  );
+  assert_eq!(mv.to_string(), "e2e4");
+
+  let promo: Move = "e7e8q".parse().unwrap();
+  assert_eq!(promo.kind(), MoveKind::Promotion(Queen));
+  assert_eq!(promo.to_string(), "e7e8q");
+  assert_eq!(
+    "a2a1N".parse::<Move>().unwrap().kind(),
+    MoveKind::Promotion(Knight)
+  );
+
+  // generated moves print the same way, castling as the king's move
+  let board = Board::from_fen("4k3/1P6/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+  let mut all: Vec<String> = board
+    .moves_for_player()
+    .iter()
+    .map(|mv| mv.to_string())
+    .collect();
+  all.sort();
+  for s in ["b7b8n", "b7b8q", "e1g1", "h1h8"] {
+    assert!(all.contains(&s.to_string()), "{s}");
+  }
+
+  assert_eq!(
+    "e2".parse::<Move>(),
+    Err(ParseMoveError::InvalidLength("e2".to_string()))
+  );
+  assert_eq!(
+    "e2e9".parse::<Move>(),
+    Err(ParseMoveError::InvalidSquare(ParseSquareError(
+      "e9".to_string()
+    )))
+  );
+  assert_eq!(
+    "e7e8k".parse::<Move>(),
+    Err(ParseMoveError::InvalidPromotion("k".to_string()))
+  );
+}
+
+#[test]
+fn uci_move_list_replays() {
+  // Ruy Lopez exchange variation with short castling
+  let mut board = Board::new();
+  for s in "e2e4 e7e5 g1f3 b8c6 f1b5 a7a6 b5c6 d7c6 e1g1".split(' ') {
+    board = board.try_apply(s.parse().unwrap()).unwrap();
+  }
+  assert_eq!(
+    board.to_fen(),
+    "r1bqkbnr/1pp2ppp/p1p5/4p3/4P3/5N2/PPPP1PPP/RNBQ1RK1 b kq - 1 5"
+  );
+}